@@ -2,6 +2,7 @@ mod metaheuristics;
 mod prcpsp;
 
 use std::env;
+use std::fs;
 use std::time::Instant;
 
 use crate::prcpsp::project::Project as Project;
@@ -10,47 +11,122 @@ use crate::prcpsp::rn_resource::RnResource as RnResource;
 use crate::prcpsp::rn_resource_usage::RnResourceUsage as RnResourceUsage;
 use crate::prcpsp::sa_state::SaState as SaState;
 use crate::prcpsp::random_rcpsp as random_rcpsp;
+use crate::prcpsp::psplib as psplib;
+use crate::prcpsp::patterson as patterson;
+use crate::prcpsp::instance as instance;
+use crate::prcpsp::solution::Solution as Solution;
+use crate::prcpsp::validator as validator;
+use crate::prcpsp::validator::Violation as Violation;
+use crate::prcpsp::bounds as bounds;
+use crate::prcpsp::bounds::Bound as Bound;
+use crate::prcpsp::milp as milp;
+use crate::prcpsp::csv_project as csv_project;
+use crate::prcpsp::schedule_export as schedule_export;
+use crate::prcpsp::calendar as calendar;
+use crate::prcpsp::window as window;
+use crate::prcpsp::preemption as preemption;
+use crate::prcpsp::relation as relation;
+use crate::prcpsp::portfolio as portfolio;
+use crate::prcpsp::objective as objective;
+use crate::prcpsp::worker as worker;
+use crate::prcpsp::stochastic as stochastic;
+use crate::prcpsp::availability as availability;
+use crate::prcpsp::decoder::Decoder as Decoder;
+use crate::prcpsp::objective::Objective as Objective;
 use crate::prcpsp::utils as utils;
 
 use crate::metaheuristics::simulated_annealing::simulated_annealing as sa;
 use crate::metaheuristics::tabu_search::tabu_search as ts;
 use crate::metaheuristics::state::State as State;
+use crate::metaheuristics::cost::Cost as Cost;
 
 fn main() {
     let args: Vec<String> = env::args().collect();
     let metaheuristic = args[1].as_str();
     let filename = args[2].as_str();
 
-    let params = utils::read_random_prcpsp(filename.to_string());
-    let random_seed = params[0].into();
-    let random_cost = params[1];
-    let random_activities = params[2];
-    let random_resources = params[3];
-    let random_resources_max_capacity = params[4];
-    let random_optimum_project = random_rcpsp::get_random_rcpsp(random_seed, random_cost, random_activities,
-        random_resources, random_resources_max_capacity);
-    let optimum_state = SaState::from_project_planned(random_optimum_project.clone(), random_seed);
-
-    println!(" Optimo {:?}", optimum_state.planning);
-    println!("        {:?}", optimum_state.times);
-    println!(">>>>> Costo {:?}", optimum_state.get_makespan());
-    utils::write_svg(optimum_state.get_svg(), "optimum.svg");
-
-    let mut random_project = random_optimum_project.clone();
-    for a in &mut random_project.activities {
+    match metaheuristic {
+        "VALIDATE" => return validate_solution(filename, &args),
+        "MONTECARLO" => return simulate_solution(filename, &args),
+        "MILP" => return export_milp(filename, &args),
+        "MILP-SOL" => return import_milp(filename, &args),
+        _ => {}
+    }
+
+    let (mut project, random_params) = load_project(filename, &args);
+    let mut optimum_state = None;
+    if project.activities.iter().all(|x| x.start_time != -1) {
+        let state = SaState::from_project_planned(project.clone(), 0);
+        println!(" Optimo {:?}", state.planning);
+        println!("        {:?}", state.times);
+        println!(">>>>> Costo {:?}", state.get_makespan());
+        utils::write_svg(state.get_svg(), "optimum.svg");
+        optimum_state = Some(state);
+    }
+    if random_params.is_some() {
+        instance::write_instance(&project, &utils::output_path("instance.prj")).unwrap_or_else(|e| panic!("{}", e));
+        instance::write_sm(&project, &utils::output_path("instance.sm")).unwrap_or_else(|e| panic!("{}", e));
+    }
+
+    for a in &mut project.activities {
         a.start_time = -1;
     }
 
     let seed = args[3].parse::<u64>().unwrap();
     let m = args[4].parse::<u32>().unwrap();
-    random_project = random_project.pre_emptive_project(m);
-
-    let initial = SaState::new(random_project.clone(), seed);
+    let original = project.clone();
+    project = project.pre_emptive_project(m);
+
+    let name = utils::instance_name(filename);
+    let mut saved = None;
+    let mut initial = match utils::get_option(&args, "--initial") {
+        Some(file) => {
+            let solution = Solution::read(&file).unwrap_or_else(|e| panic!("{}", e));
+            if solution.instance != name || solution.interruptions != m {
+                panic!("La solucion {} es del ejemplar {} con {} interrupciones, no de {} con {}", file, solution.instance, solution.interruptions, name, m);
+            }
+            let mut project = project.clone();
+            solution.set_modes(&mut project).unwrap_or_else(|e| panic!("{}", e));
+            solution.set_capacities(&mut project).unwrap_or_else(|e| panic!("{}", e));
+            saved = solution.decoder;
+            SaState::from_planning(project, solution.planning, seed).unwrap_or_else(|e| panic!("{}", e))
+        }
+        None => SaState::new(project.clone(), seed),
+    };
+    if let Some(value) = utils::get_option(&args, "--objective") {
+        let objective = Objective::from_str(&value).unwrap_or_else(|| panic!("Objetivo invalido '{}'", value));
+        if objective == Objective::CapacityCost && project.activities.last().is_none_or(|x| x.deadline < 0) {
+            panic!("El objetivo cost requiere la fecha limite del proyecto (--project-deadline)");
+        }
+        initial.set_objective(objective);
+    }
+    let decoder = get_decoder(&args, Some(seed), saved);
+    if decoder != Decoder::Serial {
+        initial.set_decoder(decoder);
+    }
+    println!(" Decodificador: {}", decoder);
+    let justify = utils::get_option(&args, "--justify");
+    match justify.as_deref() {
+        Some("search") => initial.set_justification(true),
+        Some("best") | None => {}
+        Some(value) => panic!("Justificacion invalida '{}'", value),
+    }
     utils::write_svg(initial.get_svg(), "initial.svg");
     println!("{:?}", initial.planning);
     println!("{:?}", initial.times);
     println!(">>>>> Costo {:?}", initial.get_makespan());
 
+    let best_known = match utils::get_option(&args, "--bounds") {
+        Some(file) => {
+            let table = bounds::read_bounds(&file).unwrap_or_else(|e| panic!("{}", e));
+            if !table.contains_key(&name) {
+                println!(" El ejemplar {} no esta en {}", name, file);
+            }
+            table.get(&name).copied()
+        }
+        None => optimum_state.as_ref().map(|x| Bound::new(x.get_makespan(), x.get_makespan())),
+    };
+
     let start : Instant;
     let seconds : u64;
     let time : String;
@@ -64,18 +140,33 @@ fn main() {
 
             start = Instant::now();
             let (best,log) = sa::simulated_annealing(initial.clone(), iterations, temperature, decrement, epsilon, seed);
+            let best = justify_best(best, &justify);
             seconds = start.elapsed().as_secs();
             time = utils::get_time(seconds);
-            utils::write_log_sa(best.to_string(), best.get_cost(), iterations, temperature, epsilon, decrement, seed, log, time.clone(), m);
+            utils::write_log_sa(best.to_string(), best.get_cost().text(), iterations, temperature, epsilon, decrement, seed, log, time.clone(), m);
 
             println!("\n  <<<<<<<<<<<<<<<<<<<<<<<<<<<<<<<<< \n  Mejor solucion: \n {} ", best.to_string());
-            println!(" Costo: {}", best.get_cost());
+            println!(" Costo: {}", best.get_cost().text());
             println!(" Tiempo {:?}", time );
-            println!(">>>>>>>>> Optimo {:?}", optimum_state.planning);
-            println!(">>>>>>>>>        {:?}", optimum_state.times);
-
-            utils::write_log_random(random_seed, random_cost, random_activities, random_resources, random_resources_max_capacity, initial.get_cost());
+            print_optimum(&optimum_state);
+            print_gap(&best_known, &best);
+            print_violations(&validator::validate(&best.project, &best.planning, &best.times));
+            print_missed_relations(&best);
+            print_portfolio(&best);
+            print_workers(&best);
+            print_capacities(&best);
+            if let Some(value) = utils::get_option(&args, "--montecarlo") {
+                print_simulation(&best, value.parse::<u32>().unwrap_or_else(|_| panic!("Escenarios invalidos '{}'", value)), &args);
+            }
+
+            write_log_instance(&random_params, filename, initial.get_cost().text());
+            utils::write_log_decoder(&decoder.to_string());
+            if let Some(bound) = best_known.filter(|_| has_gap(&best)) {
+                utils::write_log_gap(bound.upper, bound.gap(best.get_makespan() as f64));
+            }
             utils::write_svg(best.to_file(), "best.svg");
+            Solution::from_state(&best, &name, m).write(&utils::output_path("best.sol")).unwrap_or_else(|e| panic!("{}", e));
+            write_schedule(&original, &best, &name);
         }
         "TS" => {
             let tabu_time = args[5].parse::<u32>().unwrap();
@@ -84,24 +175,409 @@ fn main() {
 
             start = Instant::now();
             let (best,log) = ts::tabu_search(initial.clone(), tabu_time, neighbors, iterations);
+            let best = justify_best(best, &justify);
             seconds = start.elapsed().as_secs();
             time = utils::get_time(seconds);
-            utils::write_log_ts(best.to_string(), best.get_cost(), tabu_time, neighbors, iterations, seed, log, time.clone(), m);
+            utils::write_log_ts(best.to_string(), best.get_cost().text(), tabu_time, neighbors, iterations, seed, log, time.clone(), m);
 
             println!("\n  <<<<<<<<<<<<<<<<<<<<<<<<<<<<<<<<< \n  Mejor solucion: \n {} ", best.to_string());
-            println!(" Costo: {}", best.get_cost());
+            println!(" Costo: {}", best.get_cost().text());
             println!(" Tiempo {:?}", time );
-            println!(">>>>>>>>> Optimo {:?}", optimum_state.planning);
-            println!(">>>>>>>>>        {:?}", optimum_state.times);
-
-            utils::write_log_random(random_seed, random_cost, random_activities, random_resources, random_resources_max_capacity, initial.get_cost());
+            print_optimum(&optimum_state);
+            print_gap(&best_known, &best);
+            print_violations(&validator::validate(&best.project, &best.planning, &best.times));
+            print_missed_relations(&best);
+            print_portfolio(&best);
+            print_workers(&best);
+            print_capacities(&best);
+            if let Some(value) = utils::get_option(&args, "--montecarlo") {
+                print_simulation(&best, value.parse::<u32>().unwrap_or_else(|_| panic!("Escenarios invalidos '{}'", value)), &args);
+            }
+
+            write_log_instance(&random_params, filename, initial.get_cost().text());
+            utils::write_log_decoder(&decoder.to_string());
+            if let Some(bound) = best_known.filter(|_| has_gap(&best)) {
+                utils::write_log_gap(bound.upper, bound.gap(best.get_makespan() as f64));
+            }
             utils::write_svg(best.to_file(), "best.svg");
+            Solution::from_state(&best, &name, m).write(&utils::output_path("best.sol")).unwrap_or_else(|e| panic!("{}", e));
+            write_schedule(&original, &best, &name);
         }
         _ => panic!("La metaheuristica no se encontro"),
     }
 
 }
 
+/**
+* Load the project to solve from a file, with the generalized precedence relations in
+* option --relations, the capacity periods of the calendar in option --calendar,
+* the release times and deadlines of activities in option --windows, their
+* maximum interruptions in option --interruptions, the overhead of resuming
+* them in option --resume, their weights, due dates and cash flows in option
+* --costs, the workers in option --workers, the skills required by activities
+* in option --skills, the distributions of their durations in option --durations,
+* the unit costs of resources in option --prices and the deadline of the project
+* in option --project-deadline if they're given.
+* filename: path to the instance.
+* args: program arguments.
+*/
+fn load_project(filename: &str, args: &[String]) -> (Project, Option<Vec<u32>>) {
+    let (mut project, random_params) = read_project(filename, args);
+    if let Some(file) = utils::get_option(args, "--relations") {
+        relation::read_relations(&mut project, &file).unwrap_or_else(|e| panic!("{}", e));
+    }
+    if let Some(file) = utils::get_option(args, "--calendar") {
+        calendar::read_calendar(&mut project, &file).unwrap_or_else(|e| panic!("{}", e));
+    }
+    if let Some(file) = utils::get_option(args, "--windows") {
+        window::read_windows(&mut project, &file).unwrap_or_else(|e| panic!("{}", e));
+    }
+    if let Some(file) = utils::get_option(args, "--interruptions") {
+        preemption::read_interruptions(&mut project, &file).unwrap_or_else(|e| panic!("{}", e));
+    }
+    if let Some(file) = utils::get_option(args, "--resume") {
+        preemption::read_resume(&mut project, &file).unwrap_or_else(|e| panic!("{}", e));
+    }
+    if let Some(file) = utils::get_option(args, "--costs") {
+        objective::read_costs(&mut project, &file).unwrap_or_else(|e| panic!("{}", e));
+    }
+    if let Some(file) = utils::get_option(args, "--workers") {
+        let capacities : Vec<u32> = project.resources.iter().map(|x| x.capacity).collect();
+        worker::read_workers(&mut project, &file).unwrap_or_else(|e| panic!("{}", e));
+        for (resource, capacity) in project.resources.iter().zip(capacities) {
+            if resource.capacity != capacity {
+                println!(" La capacidad {} de {} se reemplaza por sus {} trabajadores", capacity, resource.name, resource.capacity);
+            }
+        }
+    }
+    if let Some(file) = utils::get_option(args, "--skills") {
+        worker::read_skills(&mut project, &file).unwrap_or_else(|e| panic!("{}", e));
+    }
+    if let Some(file) = utils::get_option(args, "--durations") {
+        stochastic::read_distributions(&mut project, &file).unwrap_or_else(|e| panic!("{}", e));
+    }
+    if let Some(file) = utils::get_option(args, "--prices") {
+        availability::read_prices(&mut project, &file).unwrap_or_else(|e| panic!("{}", e));
+    }
+    if let Some(value) = utils::get_option(args, "--project-deadline") {
+        let deadline = value.parse::<i32>().unwrap_or_else(|_| panic!("Fecha limite invalida '{}'", value));
+        availability::set_deadline(&mut project, deadline).unwrap_or_else(|e| panic!("{}", e));
+    }
+    (project, random_params)
+}
+
+/**
+* Read the project to solve from a file.
+* PSPLIB (.sm and .mm), Patterson (.rcp), text format (.prj) and CSV (.csv) instances are read as they are,
+* a portfolio (.pf) joins the projects of its files in one project sharing the resources of the portfolio,
+* any other file has the parameters for a random instance, which are also returned.
+* The resources of a CSV instance are in the file of option --resources, by default <name>-resources.csv.
+* filename: path to the instance.
+* args: program arguments.
+*/
+fn read_project(filename: &str, args: &[String]) -> (Project, Option<Vec<u32>>) {
+    if filename.ends_with(".csv") {
+        let resources = utils::get_option(args, "--resources").unwrap_or_else(|| csv_project::resources_file(filename));
+        let project = csv_project::read_csv(filename, &resources).unwrap_or_else(|e| panic!("{}", e));
+        return (project, None);
+    }
+    if filename.ends_with(".sm") || filename.ends_with(".mm") {
+        let project = psplib::read_sm(filename).unwrap_or_else(|e| panic!("{}", e));
+        return (project, None);
+    }
+    if filename.ends_with(".prj") {
+        let project = instance::read_instance(filename).unwrap_or_else(|e| panic!("{}", e));
+        return (project, None);
+    }
+    if filename.ends_with(".rcp") {
+        let project = patterson::read_rcp(filename).unwrap_or_else(|e| panic!("{}", e));
+        return (project, None);
+    }
+    if filename.ends_with(".pf") {
+        let project = portfolio::read_portfolio(filename, |file| Ok(read_project(file, args).0)).unwrap_or_else(|e| panic!("{}", e));
+        return (project, None);
+    }
+    let params = utils::read_random_prcpsp(filename.to_string());
+    let mut project = random_rcpsp::get_random_rcpsp(params[0].into(), params[1], params[2], params[3], params[4]);
+    if params.len() > 5 {
+        random_rcpsp::add_nr_resources(&mut project, params[0].into(), params[5]);
+    }
+    (project, Some(params))
+}
+
+/**
+* Check a solution file against its instance and print the violations found.
+* Exit with an error if the solution is infeasible.
+* filename: path to the instance.
+* args: program arguments, VALIDATE <file> <solution>.
+*/
+fn validate_solution(filename: &str, args: &[String]) {
+    let (project, _) = load_project(filename, args);
+    let solution = Solution::read(&args[3]).unwrap_or_else(|e| panic!("{}", e));
+    let mut project = project.pre_emptive_project(solution.interruptions);
+    solution.set_modes(&mut project).unwrap_or_else(|e| panic!("{}", e));
+    solution.set_workers(&mut project).unwrap_or_else(|e| panic!("{}", e));
+    solution.set_capacities(&mut project).unwrap_or_else(|e| panic!("{}", e));
+    let violations = validator::validate(&project, &solution.planning, &solution.times);
+    print_violations(&violations);
+    if let Some(decoder) = solution.decoder {
+        let mut state = SaState::from_planning(project, solution.planning.clone(), 0).unwrap_or_else(|e| panic!("{}", e));
+        state.set_decoder(decoder);
+        if state.times != solution.times {
+            println!(" Los tiempos no son los del decodificador {}: {:?}", decoder, state.times);
+        }
+    }
+    if !violations.is_empty() {
+        std::process::exit(1);
+    }
+}
+
+/**
+* Write the time-indexed model of the pre-emptive project in LP and MPS format.
+* The horizon is the makespan of the initial solution if not given.
+* filename: path to the instance.
+* args: program arguments, MILP <file> <m> [horizon].
+*/
+fn export_milp(filename: &str, args: &[String]) {
+    let (mut project, _) = load_project(filename, args);
+    for a in &mut project.activities {
+        a.start_time = -1;
+    }
+    let m = args[3].parse::<u32>().unwrap();
+    let project = project.pre_emptive_project(m);
+    let horizon = match args.get(4) {
+        Some(horizon) => horizon.parse::<i32>().unwrap(),
+        None => SaState::new(project.clone(), 0).get_makespan() as i32,
+    };
+    let model = milp::get_model(&project, horizon).unwrap_or_else(|e| panic!("{}", e));
+    fs::write(utils::output_path("model.lp"), milp::to_lp(&model)).expect("No se pudo escribir un archivo");
+    fs::write(utils::output_path("model.mps"), milp::to_mps(&model)).expect("No se pudo escribir un archivo");
+    println!(" Modelo con {} variables y {} restricciones, horizonte {}", model.binaries.len(), model.constraints.len(), horizon);
+}
+
+/**
+* Read the solution of a MIP solver for the model written by export_milp,
+* check it and write it as SVG and solution file.
+* filename: path to the instance.
+* args: program arguments, MILP-SOL <file> <m> <solver solution>.
+*/
+fn import_milp(filename: &str, args: &[String]) {
+    let (mut project, _) = load_project(filename, args);
+    for a in &mut project.activities {
+        a.start_time = -1;
+    }
+    let m = args[3].parse::<u32>().unwrap();
+    let project = project.pre_emptive_project(m);
+    let state = milp::read_solution(project, &args[4], 0).unwrap_or_else(|e| panic!("{}", e));
+    println!(" Solucion {:?}", state.planning);
+    println!("          {:?}", state.times);
+    println!(" Costo: {}", state.get_makespan());
+    print_violations(&validator::validate(&state.project, &state.planning, &state.times));
+    utils::write_svg(state.get_svg(), "milp.svg");
+    let mut solution = Solution::from_state(&state, &utils::instance_name(filename), m);
+    solution.decoder = None;
+    solution.write(&utils::output_path("milp.sol")).unwrap_or_else(|e| panic!("{}", e));
+}
+
+/**
+* Write the schedule of the best state as CSV and MS Project XML in output directory.
+* original: project before splitting activities.
+* best: best state found.
+* name: name of the instance.
+*/
+fn write_schedule(original: &Project, best: &SaState, name: &str) {
+    fs::write(utils::output_path("best.csv"), schedule_export::to_csv(original, best))
+        .unwrap_or_else(|e| panic!("No se pudo escribir el archivo best.csv: {}", e));
+    fs::write(utils::output_path("best.xml"), schedule_export::to_msproject(original, best, name))
+        .unwrap_or_else(|e| panic!("No se pudo escribir el archivo best.xml: {}", e));
+}
+
+/**
+* Evaluate the activity list of a solution file over sampled scenarios of durations.
+* filename: path to the instance.
+* args: program arguments, MONTECARLO <file> <solution> <scenarios>.
+*/
+fn simulate_solution(filename: &str, args: &[String]) {
+    let (project, _) = load_project(filename, args);
+    let solution = Solution::read(&args[3]).unwrap_or_else(|e| panic!("{}", e));
+    let scenarios = match args.get(4).map(|x| x.parse::<u32>()) {
+        Some(Ok(scenarios)) => scenarios,
+        Some(Err(_)) => panic!("Escenarios invalidos '{}'", args[4]),
+        None => panic!("Falta el numero de escenarios"),
+    };
+    let mut project = project.pre_emptive_project(solution.interruptions);
+    solution.set_modes(&mut project).unwrap_or_else(|e| panic!("{}", e));
+    solution.set_workers(&mut project).unwrap_or_else(|e| panic!("{}", e));
+    solution.set_capacities(&mut project).unwrap_or_else(|e| panic!("{}", e));
+    let mut state = SaState::from_planning(project, solution.planning, 0).unwrap_or_else(|e| panic!("{}", e));
+    state.set_decoder(get_decoder(args, None, solution.decoder));
+    println!(" Makespan nominal: {}", state.get_makespan());
+    print_simulation(&state, scenarios, args);
+}
+
+/**
+* Print the expected makespan, percentiles and probability of meeting the deadline of the
+* activity list of a state over sampled scenarios of durations, and save them in log.
+* Scenarios are the same for every activity list of an instance. The deadline is the one of
+* option --deadline, the deadline of the end activity or the makespan of the state.
+* state: state evaluated.
+* scenarios: number of scenarios.
+* args: program arguments.
+*/
+fn print_simulation(state: &SaState, scenarios: u32, args: &[String]) {
+    let simulation = stochastic::simulate(&state.project, &state.planning, scenarios, 0, state.decoder).unwrap_or_else(|e| panic!("{}", e));
+    let deadline = match utils::get_option(args, "--deadline") {
+        Some(value) => value.parse::<i32>().unwrap_or_else(|_| panic!("Fecha limite invalida '{}'", value)),
+        None => state.project.activities.last().map(|x| x.deadline).filter(|x| *x >= 0).unwrap_or(state.get_makespan() as i32),
+    };
+    let percentiles : Vec<(u32, u32)> = [50, 80, 90, 95].iter().map(|p| (*p, simulation.percentile(*p))).collect();
+    println!(" Escenarios: {} Makespan esperado: {:.2}", scenarios, simulation.expected());
+    for (p, makespan) in &percentiles {
+        println!(" Percentil {}: {}", p, makespan);
+    }
+    println!(" Probabilidad de terminar en {}: {:.2}%", deadline, 100.0 * simulation.on_time(deadline));
+    utils::write_log_simulation(scenarios, simulation.expected(), &percentiles, deadline, simulation.on_time(deadline));
+}
+
+/**
+* Return the decoder of option --decoder, or else the one saved in a solution,
+* serial by default. With random, the serial or the parallel decoder is chosen
+* with the seed of the run, and without a run the saved one is kept.
+* args: program arguments.
+* seed: seed of the run, if there is one.
+* saved: decoder of the solution loaded, if any.
+*/
+fn get_decoder(args: &[String], seed: Option<u64>, saved: Option<Decoder>) -> Decoder {
+    match utils::get_option(args, "--decoder") {
+        Some(value) if value == "random" => match (seed, saved) {
+            (Some(seed), _) => Decoder::for_run(seed),
+            (None, Some(decoder)) => decoder,
+            (None, None) => panic!("La solucion no tiene decodificador, use --decoder serial, parallel o hybrid"),
+        },
+        Some(value) => Decoder::from_str(&value).unwrap_or_else(|| panic!("Decodificador invalido '{}'", value)),
+        None => saved.unwrap_or(Decoder::Serial),
+    }
+}
+
+/**
+* Improve the best state found by double justification if option --justify is given,
+* printing its cost before and after.
+* best: best state found.
+* justify: value of option --justify.
+*/
+fn justify_best(mut best: SaState, justify: &Option<String>) -> SaState {
+    if justify.is_some() {
+        let cost = best.get_cost();
+        best.justify();
+        println!(" Justificacion: costo {} -> {}", cost.text(), best.get_cost().text());
+    }
+    best
+}
+
+/**
+* Print the violations found in a schedule.
+*/
+fn print_violations(violations: &[Violation]) {
+    if violations.is_empty() {
+        println!(" Solucion factible");
+    }
+    for violation in violations {
+        println!(" Solucion no factible: {}", violation);
+    }
+}
+
+/**
+* Print the relations whose lags the decoder couldn't meet, between the original activities.
+*/
+fn print_missed_relations(state: &SaState) {
+    let parent = |id: u32| state.project.activities.iter().find(|x| x.id == id).map_or(id, |x| x.get_parent());
+    for (relation, excess) in &state.missed_relations {
+        println!(" Retraso no cumplido: relacion {} de {} a {} por {} unidades", relation.kind, parent(relation.from), parent(relation.to), excess);
+    }
+}
+
+/**
+* Print the finish time and delay of each project of a portfolio.
+*/
+fn print_portfolio(state: &SaState) {
+    for subproject in &state.project.subprojects {
+        let finish = state.project.subproject_finish(subproject);
+        println!(" Proyecto {}: termina en {} con retraso {} (peso {})", subproject.name, finish, (finish - subproject.due_date).max(0), subproject.weight);
+    }
+}
+
+/**
+* Print the workers assigned to each activity, only for projects with workers.
+*/
+fn print_workers(state: &SaState) {
+    for activity in state.project.activities.iter().filter(|x| !x.workers.is_empty()) {
+        let names : Vec<&str> = activity.workers.iter()
+                                                .filter_map(|w| state.project.workers.iter().find(|x| x.id == *w))
+                                                .map(|x| x.name.as_str())
+                                                .collect();
+        println!(" Actividad {} ({}) en {}: {}", activity.id, activity.get_parent(), activity.start_time, names.join(", "));
+    }
+}
+
+/**
+* Print the capacity of each resource and its cost, only when the search chooses the capacities.
+*/
+fn print_capacities(state: &SaState) {
+    if state.objective != Objective::CapacityCost {
+        return;
+    }
+    for resource in &state.project.resources {
+        println!(" Recurso {}: capacidad {} costo {}", resource.name, resource.capacity, resource.capacity * resource.cost);
+    }
+    println!(" Costo de capacidad: {}", state.project.capacity_cost());
+}
+
+/**
+* Print the constructed optimum, only known for random instances.
+*/
+fn print_optimum(optimum_state: &Option<SaState>) {
+    if let Some(optimum_state) = optimum_state {
+        println!(">>>>>>>>> Optimo {:?}", optimum_state.planning);
+        println!(">>>>>>>>>        {:?}", optimum_state.times);
+    }
+}
+
+/**
+* Print the gap between the makespan of a state and the best known values of the instance,
+* if the state minimizes the makespan and it's feasible.
+*/
+fn print_gap(best_known: &Option<Bound>, state: &SaState) {
+    if let Some(bound) = best_known {
+        if !has_gap(state) {
+            println!(" Sin brecha: el objetivo no es el makespan o la solucion tiene penalizacion");
+            return;
+        }
+        let makespan = state.get_makespan() as f64;
+        println!(" Mejor conocido: {} Brecha: {:.2}%", bound.upper, bound.gap(makespan));
+        if bound.lower != bound.upper {
+            println!(" Cota inferior: {} Brecha: {:.2}%", bound.lower, bound.gap_lower(makespan));
+        }
+    }
+}
+
+/**
+* Return true if the gap to the best known makespan applies to a state, as it minimizes
+* the makespan and it doesn't have penalty.
+*/
+fn has_gap(state: &SaState) -> bool {
+    state.objective == Objective::Makespan && state.get_penalty() == 0
+}
+
+/**
+* Write the instance data in log.
+*/
+fn write_log_instance(random_params: &Option<Vec<u32>>, filename: &str, initial_cost: String) {
+    match random_params {
+        Some(params) => utils::write_log_random(params[0].into(), params[1], params[2], params[3], params[4], initial_cost),
+        None => utils::write_log_file(filename, initial_cost),
+    }
+}
+
 #[allow(dead_code)]
 fn initial() -> Project {
     let mut initial = Activity::new(1,-1,String::from("1"),vec![],vec![],vec![],0,-1);
@@ -0,0 +1,101 @@
+/**
+* Cost of a state, minimized by the metaheuristics.
+* Costs are compared with their order, so any ordered type as an integer,
+* a real number or a tuple, compared lexicographically, can be a cost.
+*/
+pub trait Cost: Copy + PartialOrd {
+    /**
+    * Return a cost greater than any other, the cost before any neighbor is found.
+    */
+    fn max_value() -> Self;
+
+    /**
+    * Return how much worse this cost is than another one, negative if it's better.
+    * Simulated annealing accepts a worse neighbor with a probability that decreases with it.
+    * other: cost to compare.
+    */
+    fn difference(&self, other: &Self) -> f64;
+
+    /**
+    * Return the cost as text for the output and the log.
+    */
+    fn text(&self) -> String;
+}
+
+impl Cost for u32 {
+    fn max_value() -> u32 {
+        u32::MAX
+    }
+
+    fn difference(&self, other: &u32) -> f64 {
+        *self as f64 - *other as f64
+    }
+
+    fn text(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl Cost for f64 {
+    fn max_value() -> f64 {
+        f64::INFINITY
+    }
+
+    fn difference(&self, other: &f64) -> f64 {
+        self - other
+    }
+
+    fn text(&self) -> String {
+        self.to_string()
+    }
+}
+
+/**
+* Pair of costs compared lexicographically, where the second one only
+* breaks ties of the first one (e.g. penalty and then makespan).
+* When the first ones differ simulated annealing takes their difference, so the first
+* cost should be scaled for a unit of it to outweigh any difference of the second one.
+* The pair is written as its second cost alone when the first one is 0.
+*/
+impl<A: Cost + Default, B: Cost> Cost for (A, B) {
+    fn max_value() -> (A, B) {
+        (A::max_value(), B::max_value())
+    }
+
+    fn difference(&self, other: &(A, B)) -> f64 {
+        if self.0 == other.0 {
+            self.1.difference(&other.1)
+        } else {
+            self.0.difference(&other.0)
+        }
+    }
+
+    fn text(&self) -> String {
+        if self.0 == A::default() {
+            return self.1.text();
+        }
+        format!("({}, {})", self.0.text(), self.1.text())
+    }
+}
+
+#[cfg(test)]
+ mod tests {
+     use crate::metaheuristics::cost::Cost as Cost;
+
+     #[test]
+     fn test_costs() {
+         assert_eq!(3u32.difference(&5), -2.0);
+         assert_eq!(2.5f64.difference(&1.0), 1.5);
+         assert_eq!(<u32 as Cost>::max_value(), u32::MAX);
+         assert_eq!(12.0f64.text(), "12");
+
+         let a = (0u32, 12.5f64);
+         let b = (1u32, 3.0f64);
+         assert!(a < b);
+         assert!(b < <(u32, f64)>::max_value());
+         assert_eq!(b.difference(&a), 1.0);
+         assert_eq!(a.difference(&(0, 10.0)), 2.5);
+         assert_eq!(a.text(), "12.5");
+         assert_eq!(b.text(), "(1, 3)");
+     }
+ }
@@ -1,3 +1,4 @@
 pub mod state;
+pub mod cost;
 pub mod simulated_annealing;
 pub mod tabu_search;
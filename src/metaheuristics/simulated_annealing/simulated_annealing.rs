@@ -1,8 +1,11 @@
 use crate::metaheuristics::state::State as State;
+use crate::metaheuristics::cost::Cost as Cost;
 use rand::{Rng, SeedableRng,StdRng};
 
 /**
 * Simulated annealing metaheuristic.
+* A worse neighbor is accepted with probability exp(-difference / temperature),
+* with the difference between the costs of the neighbor and the current state.
 *
 * initial_state: initial state.
 * iterations: iterations per temperature level.
@@ -11,7 +14,7 @@ use rand::{Rng, SeedableRng,StdRng};
 * epsilon: minimum reached temperature to finish.
 * seed: seed for pseudo-random numbers.
 */
-pub fn simulated_annealing(initial_state: impl State + Clone, iterations: u32, mut temperature : f32, decrement: f32, epsilon: f32, seed: u64) -> (impl State, Vec<String>) {
+pub fn simulated_annealing<S: State + Clone>(initial_state: S, iterations: u32, mut temperature : f32, decrement: f32, epsilon: f32, seed: u64) -> (S, Vec<String>) {
     let mut log = vec![];
     let mut current_state = initial_state.clone();
     let mut optimum = initial_state.clone();
@@ -31,8 +34,8 @@ pub fn simulated_annealing(initial_state: impl State + Clone, iterations: u32, m
                 // Cannot find another solution
                 break;
             }
-            let delta = neighbor_cost as i32 - current_state.get_cost() as i32;
-            if delta <= 0 {
+            let delta = neighbor_cost.difference(&current_state.get_cost());
+            if delta <= 0.0 {
                 current_state.set_neighbor(movement);
             } else {
                 let diff = (-1.0 * (delta as f32)) / temperature;
@@ -45,12 +48,12 @@ pub fn simulated_annealing(initial_state: impl State + Clone, iterations: u32, m
         }
         println!("\n  >>>>>>>>>>> \n  Temperatura actual: {} ", temperature );
         println!("  Ejemplar: \n {}",current_state.to_string());
-        println!("  Costo: {}", current_state.get_cost());
+        println!("  Costo: {}", current_state.get_cost().text());
         println!("  Iteracion: {}/{}", iteration, total);
-        println!("  Optimo {} Actual {}", optimum.get_cost(), current_state.get_cost());
+        println!("  Optimo {} Actual {}", optimum.get_cost().text(), current_state.get_cost().text());
         temperature *= decrement;
         iteration += 1;
-        log.push(current_state.get_cost().to_string());
+        log.push(current_state.get_cost().text());
         if current_state.get_cost() < optimum.get_cost() {
             optimum = current_state.clone();
         }
@@ -1,7 +1,10 @@
+use crate::metaheuristics::cost::Cost as Cost;
+
 pub trait State {
-    fn get_neighbor(&mut self) -> (u32, usize, Vec<u32>);
+    type Cost: Cost;
+    fn get_neighbor(&mut self) -> (Self::Cost, usize, Vec<u32>);
     fn set_neighbor(&mut self, movement: usize);
-    fn get_cost(&self) -> u32;
+    fn get_cost(&self) -> Self::Cost;
     fn to_string(&self) -> String;
     fn to_file(&self) -> String;
     fn svg(&self) -> String;
@@ -1,4 +1,5 @@
 use crate::metaheuristics::state::State as State;
+use crate::metaheuristics::cost::Cost as Cost;
 use crate::metaheuristics::tabu_search::tabu_mv::TabuMv as TabuMv;
 /**
 * Tabu search metaheuristic.
@@ -7,7 +8,7 @@ use crate::metaheuristics::tabu_search::tabu_mv::TabuMv as TabuMv;
 * neighbors: admissible neighbors to search each time.
 * iterations: total iterations.
 */
-pub fn tabu_search(initial_state: impl State + Clone, tabu_time: u32, neighbors: u32 , iterations: u32) -> (impl State, Vec<String>) {
+pub fn tabu_search<S: State + Clone>(initial_state: S, tabu_time: u32, neighbors: u32 , iterations: u32) -> (S, Vec<String>) {
     let mut log : Vec<String> = vec![];
     let mut tabu_list : Vec<TabuMv> = vec![];
     let mut current_state = initial_state;
@@ -24,7 +25,7 @@ pub fn tabu_search(initial_state: impl State + Clone, tabu_time: u32, neighbors:
             optimum = current_state.clone();
         }
 
-        log.push(current_state.get_cost().to_string());
+        log.push(current_state.get_cost().text());
         update_tabu_time(&mut tabu_list);
 
         if movement != 0 {
@@ -34,10 +35,10 @@ pub fn tabu_search(initial_state: impl State + Clone, tabu_time: u32, neighbors:
 
         println!("\n  >>>>>>>>>>> \n ");
         println!("  Ejemplar: \n {}",current_state.to_string());
-        println!("  Costo: {}", current_state.get_cost());
+        println!("  Costo: {}", current_state.get_cost().text());
         println!("  Iteracion: {}/{}", limit+1, iterations);
         println!("  Lista tabu: {:?}", tabu_list);
-        println!("  Optimo {} Actual {}", optimum.get_cost(), current_state.get_cost());
+        println!("  Optimo {} Actual {}", optimum.get_cost().text(), current_state.get_cost().text());
         limit += 1;
     }
     return (optimum, log);
@@ -50,8 +51,8 @@ pub fn tabu_search(initial_state: impl State + Clone, tabu_time: u32, neighbors:
 * tabu_list: tabu struct.
 * penalty: penalty for diversification.
 */
-fn best_admissible_neighbors(current_state: &mut (impl State + Clone), neighbors: u32, tabu_list : &mut Vec<TabuMv>, optimum: &impl State) -> (u32, usize, Vec<u32>) {
-    let mut best_neighbor_cost = u32::MAX;
+fn best_admissible_neighbors<S: State + Clone>(current_state: &mut S, neighbors: u32, tabu_list : &mut Vec<TabuMv>, optimum: &S) -> (S::Cost, usize, Vec<u32>) {
+    let mut best_neighbor_cost = S::Cost::max_value();
     let mut best_movement : usize = 0;
     let mut best_activities : Vec<u32> = vec![];
     let mut admissible_neighbors = 0;
@@ -105,9 +106,7 @@ fn best_admissible_neighbors(current_state: &mut (impl State + Clone), neighbors
     if aspiration_criteria(best_neighbor_cost, optimum) {
         let is_tabu = tabu_list.iter().any(|x| x.is_tabu(best_activities.clone()));
         if is_tabu {
-            let i = tabu_list.iter().position(|x| x.movements.contains(&best_activities[0]) &&
-                                                  x.movements.contains(&best_activities[1]) &&
-                                                  x.movements.contains(&best_activities[2]) ).unwrap();
+            let i = tabu_list.iter().position(|x| x.is_tabu(best_activities.clone())).unwrap();
             tabu_list.remove(i);
         }
     }
@@ -118,7 +117,7 @@ fn best_admissible_neighbors(current_state: &mut (impl State + Clone), neighbors
 * If tabu movement gets a solution better than
 * any other seen before, then can be accepted.
 */
-fn aspiration_criteria(neighbor_cost: u32, optimum: &impl State) -> bool {
+fn aspiration_criteria<S: State>(neighbor_cost: S::Cost, optimum: &S) -> bool {
     if neighbor_cost < optimum.get_cost() {
         return true;
     }
@@ -1,4 +1,7 @@
 use crate::prcpsp::rn_resource_usage::RnResourceUsage as RnResourceUsage;
+use crate::prcpsp::nr_resource_usage::NrResourceUsage as NrResourceUsage;
+use crate::prcpsp::mode::Mode as Mode;
+use crate::prcpsp::stochastic::Distribution as Distribution;
 use core::cmp::Ordering;
 
 #[derive(Debug,Clone)]
@@ -11,11 +14,28 @@ pub struct Activity {
     pub supplies : Vec<RnResourceUsage>,
     pub duration: u32,
     pub start_time : i32,
+    pub consumptions : Vec<NrResourceUsage>,
+    pub modes : Vec<Mode>,
+    pub mode : usize,
+    pub release : i32,
+    pub deadline : i32,
+    pub max_interruptions : i32,
+    pub min_segment : u32,
+    pub max_gap : i32,
+    pub resume_time : u32,
+    pub resume_supplies : Vec<RnResourceUsage>,
+    pub resumed : bool,
+    pub weight : u32,
+    pub due_date : i32,
+    pub cash_flow : i32,
+    pub workers : Vec<u32>,
+    pub distribution : Option<Distribution>,
 }
 
 impl Activity {
     pub fn new(id: u32, parent: i32, name: String, predecessors: Vec<u32>, successors: Vec<u32>, supplies: Vec<RnResourceUsage>, duration: u32, start_time: i32 ) -> Activity {
-        Activity{ id, parent, name, predecessors, successors, supplies, duration, start_time }
+        Activity{ id, parent, name, predecessors, successors, supplies, duration, start_time, consumptions: vec![], modes: vec![], mode: 0, release: 0, deadline: -1, max_interruptions: -1, min_segment: 0, max_gap: -1,
+                  resume_time: 0, resume_supplies: vec![], resumed: false, weight: 1, due_date: -1, cash_flow: 0, workers: vec![], distribution: None }
     }
 
     pub fn add_anteccessor(&mut self, id_anteccessor: u32) {
@@ -42,6 +62,17 @@ impl Activity {
         }
     }
 
+    /**
+    * Return the time units that the activity finishes after its deadline,
+    * 0 if it doesn't have a deadline or isn't planned.
+    */
+    pub fn get_lateness(&self) -> u32 {
+        if self.deadline < 0 || self.start_time == -1 {
+            return 0;
+        }
+        (self.start_time + self.duration as i32 - self.deadline).max(0) as u32
+    }
+
     pub fn get_demand(&self) -> u32 {
         let mut usage = 0;
         for s in &self.supplies {
@@ -50,6 +81,69 @@ impl Activity {
         return usage;
     }
 
+    /**
+    * Return the id of the activity in the original project,
+    * the parent for subactivities or the activity itself.
+    */
+    pub fn get_parent(&self) -> u32 {
+        if self.parent > 0 { self.parent as u32 } else { self.id }
+    }
+
+    /**
+    * Select an execution mode, updating duration, supplies and consumptions.
+    * An activity without modes only has mode 0, its current duration and supplies.
+    * mode: index of the mode in modes.
+    */
+    pub fn set_mode(&mut self, mode: usize) {
+        if self.modes.is_empty() {
+            if mode != 0 {
+                panic!("Activity {} has only one mode", self.id);
+            }
+            return;
+        }
+        self.duration = self.modes[mode].duration;
+        self.supplies = self.modes[mode].supplies.clone();
+        self.consumptions = self.modes[mode].consumptions.clone();
+        self.mode = mode;
+        self.resumed = false;
+    }
+
+    /**
+    * Return the duration of the shortest mode, the current duration for an activity without modes.
+    */
+    pub fn shortest_duration(&self) -> u32 {
+        self.modes.iter().map(|x| x.duration).min().unwrap_or(self.duration)
+    }
+
+    /**
+    * Charge or remove the overhead of resuming the activity after an interruption,
+    * its resume_time added to the duration and its resume_supplies to the supplies.
+    * resumed: true if the activity starts after an interruption.
+    */
+    pub fn set_resumed(&mut self, resumed: bool) {
+        if resumed == self.resumed || (self.resume_time == 0 && self.resume_supplies.is_empty()) {
+            return;
+        }
+        if resumed {
+            self.duration += self.resume_time;
+            for extra in &self.resume_supplies {
+                match self.supplies.iter_mut().find(|x| x.resource == extra.resource) {
+                    Some(supply) => supply.usage += extra.usage,
+                    None => self.supplies.push(extra.clone()),
+                }
+            }
+        } else {
+            self.duration -= self.resume_time;
+            for extra in &self.resume_supplies {
+                if let Some(supply) = self.supplies.iter_mut().find(|x| x.resource == extra.resource) {
+                    supply.usage -= extra.usage;
+                }
+            }
+            self.supplies.retain(|x| x.usage > 0);
+        }
+        self.resumed = resumed;
+    }
+
 }
 
 impl PartialEq for Activity {
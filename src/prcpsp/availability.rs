@@ -0,0 +1,123 @@
+use std::fs;
+
+use crate::prcpsp::project::Project as Project;
+
+/*
+* Unit costs of renewable resources, for the resource availability cost problem,
+* where the search chooses the capacity of each resource to minimize their cost
+* while the project finishes by its deadline.
+*
+* File format (lines starting with '#' are comments):
+*
+*   <resource> <unit_cost>
+*
+* where resource is the id or name of a renewable resource and unit_cost the cost of
+* each unit of its capacity. Resources without a cost keep their capacity.
+*/
+
+/**
+* Read a file of unit costs and set them to the resources of a project.
+* project: project whose resources are changed.
+* filename: path to the unit costs.
+*/
+pub fn read_prices(project: &mut Project, filename: &str) -> Result<(), String> {
+    let contents = fs::read_to_string(filename)
+            .map_err(|e| format!("No se pudo leer el archivo {}: {}", filename, e))?;
+    parse_prices(project, &contents)
+}
+
+/**
+* Set the unit costs of a text to the resources of a project.
+* project: project whose resources are changed.
+* contents: text of the unit costs.
+*/
+pub fn parse_prices(project: &mut Project, contents: &str) -> Result<(), String> {
+    for line in contents.lines().map(|x| x.trim()).filter(|x| !x.is_empty() && !x.starts_with('#')) {
+        let tokens : Vec<&str> = line.split_whitespace().collect();
+        if tokens.len() != 2 {
+            return Err(format!("Costo de recurso incompleto: '{}'", line));
+        }
+        let cost = tokens[1].parse::<u32>().map_err(|_| format!("Valor invalido '{}' en '{}'", tokens[1], line))?;
+        let resource = project.resources.iter_mut()
+                                        .find(|x| x.name == tokens[0] || x.id.to_string() == tokens[0])
+                                        .ok_or_else(|| format!("Recurso {} no encontrado", tokens[0]))?;
+        resource.cost = cost;
+    }
+    Ok(())
+}
+
+/**
+* Set the deadline of a project, as the deadline of its end activity.
+* project: project to change.
+* deadline: time when the project must be finished.
+*/
+pub fn set_deadline(project: &mut Project, deadline: i32) -> Result<(), String> {
+    if deadline < 0 {
+        return Err(format!("Fecha limite invalida {}", deadline));
+    }
+    let end = project.activities.last_mut().ok_or_else(|| String::from("Proyecto sin actividades"))?;
+    end.deadline = deadline;
+    Ok(())
+}
+
+#[cfg(test)]
+ mod tests {
+     use crate::prcpsp::availability as availability;
+     use crate::prcpsp::csv_project as csv_project;
+     use crate::prcpsp::instance as instance;
+     use crate::prcpsp::objective::Objective as Objective;
+     use crate::prcpsp::sa_state::SaState as SaState;
+     use crate::prcpsp::solution::Solution as Solution;
+     use crate::prcpsp::validator as validator;
+     use crate::metaheuristics::state::State as State;
+     use crate::metaheuristics::tabu_search::tabu_search as ts;
+     use crate::metaheuristics::tabu_search::tabu_mv::TabuMv as TabuMv;
+
+     #[test]
+     fn test_availability() {
+         let resources = "name,capacity,cost\ncrew,2,3\ncrane,1,\n";
+         let activities = "id,name,duration,predecessors,crew,crane\nA,Walls,2,,1,1\nB,Roof,2,A,2,\nC,Paint,2,,1,\nD,Floor,1,,2,\n";
+         let mut project = csv_project::parse_csv(activities, resources).unwrap();
+         availability::parse_prices(&mut project, "# por trabajador\ncrane 5\n").unwrap();
+         assert_eq!(project.resources.iter().map(|x| x.cost).collect::<Vec<u32>>(), vec![3, 5]);
+         assert_eq!(project.capacity_bounds(&project.resources[0]), (2, 6));
+         assert_eq!(project.capacity_bounds(&project.resources[1]), (1, 1));
+         assert_eq!(project.adjustable_resources(), vec![(0, 2, 6)]);
+         assert_eq!(project.capacity_cost(), 11);
+
+         // With a crew of 2 the project takes at least 5, so a deadline of 4 needs another one
+         availability::set_deadline(&mut project, 4).unwrap();
+         let mut state = SaState::new(project.pre_emptive_project(0), 14);
+         state.set_objective(Objective::CapacityCost);
+         assert!(state.get_penalty() > 0);
+         assert!(state.get_cost() > (0, 3.0 * 6.0 + 5.0));
+         let (best, _) = ts::tabu_search(state, 3, 6, 100);
+         assert_eq!(best.get_penalty(), 0);
+         assert_eq!(best.project.resources[0].capacity, 3);
+         assert_eq!(best.get_cost(), (0, 14.0));
+         assert!(validator::validate(&best.project, &best.planning, &best.times).is_empty());
+
+         // Capacity moves are tabu by resource, apart from the activities of swaps
+         let mut other = SaState::new(project.pre_emptive_project(0), 14);
+         other.set_objective(Objective::CapacityCost);
+         let (_, _, activities) = (0..20).map(|_| State::get_neighbor(&mut other)).find(|x| x.2.first() == Some(&u32::MAX)).unwrap();
+         assert_eq!(activities, vec![u32::MAX, 1]);
+         assert!(!TabuMv::new(activities, 3).is_tabu(vec![0, 1]));
+
+         let solution = Solution::parse(&Solution::from_state(&best, "racp", 0).get_string()).unwrap();
+         assert_eq!(solution.capacities, vec![3, 1]);
+         let mut loaded = project.pre_emptive_project(0);
+         solution.set_capacities(&mut loaded).unwrap();
+         assert!(validator::validate(&loaded, &solution.planning, &solution.times).is_empty());
+         assert!(!validator::validate(&project.pre_emptive_project(0), &solution.planning, &solution.times).is_empty());
+
+         let other = instance::parse_instance(&instance::to_string(&project)).unwrap();
+         assert_eq!(other.resources.iter().map(|x| x.cost).collect::<Vec<u32>>(), vec![3, 5]);
+         assert_eq!(other.activities.last().unwrap().deadline, 4);
+
+         assert!(availability::parse_prices(&mut project, "crew\n").is_err());
+         assert!(availability::parse_prices(&mut project, "crew -1\n").is_err());
+         assert!(availability::parse_prices(&mut project, "cranes 1\n").is_err());
+         assert!(availability::set_deadline(&mut project, -1).is_err());
+     }
+ }
@@ -0,0 +1,150 @@
+use std::collections::HashMap;
+use std::fs;
+
+/**
+* Best known values of an instance, lower bound and best known makespan.
+* Both are the same when the optimum is known.
+*/
+#[derive(Debug,Clone,Copy,PartialEq)]
+pub struct Bound {
+    pub lower: u32,
+    pub upper: u32,
+}
+
+impl Bound {
+    pub fn new(lower: u32, upper: u32) -> Bound {
+        Bound { lower, upper }
+    }
+
+    /**
+    * Return the gap in percent between a cost and the best known makespan.
+    * cost: cost to compare.
+    */
+    pub fn gap(&self, cost: f64) -> f32 {
+        gap(cost, self.upper)
+    }
+
+    /**
+    * Return the gap in percent between a cost and the lower bound.
+    * cost: cost to compare.
+    */
+    pub fn gap_lower(&self, cost: f64) -> f32 {
+        gap(cost, self.lower)
+    }
+}
+
+/**
+* Return the gap in percent between a cost and a reference value.
+*/
+pub fn gap(cost: f64, reference: u32) -> f32 {
+    if reference == 0 {
+        return 0.0;
+    }
+    (cost as f32 - reference as f32) * 100.0 / reference as f32
+}
+
+/**
+* Read a table with best known values keyed by instance name.
+* filename: path to the table.
+*/
+pub fn read_bounds(filename: &str) -> Result<HashMap<String, Bound>, String> {
+    let contents = fs::read_to_string(filename)
+            .map_err(|e| format!("No se pudo leer el archivo {}: {}", filename, e))?;
+    parse_bounds(&contents, &table_prefix(filename))
+}
+
+/**
+* Create the table of best known values from the contents of a file.
+*
+* PSPLIB tables (as j30opt.sm or j120lb.sm) have a header line with 'Instance' and
+* lines 'parameter instance value..', the instance name is prefix + parameter + '_' + instance,
+* as j301_1. Columns 'LB', 'UB' and 'Makespan' of the header are used, if there isn't one of
+* them the first value is both lower bound and best makespan.
+* Other tables have lines 'name best [lower]'. Lines in other format are ignored.
+* contents: text of the table.
+* prefix: prefix of instance names for PSPLIB tables, as j30.
+*/
+pub fn parse_bounds(contents: &str, prefix: &str) -> Result<HashMap<String, Bound>, String> {
+    let mut bounds : HashMap<String, Bound> = HashMap::new();
+    let mut columns : Vec<String> = vec![];
+    for line in contents.lines().map(|x| x.trim()).filter(|x| !x.is_empty() && !x.starts_with('#')) {
+        let tokens : Vec<&str> = line.split_whitespace().collect();
+        if tokens.iter().any(|x| x.to_lowercase().starts_with("inst")) {
+            columns = tokens.iter().map(|x| x.to_lowercase()).collect();
+            continue;
+        }
+        let first = tokens[0].parse::<u32>();
+        let second = tokens.get(1).map(|x| x.parse::<u32>());
+        match (first, second) {
+            (Ok(parameter), Some(Ok(instance))) => {
+                let values : Vec<u32> = tokens[2..].iter().map_while(|x| value(x)).collect();
+                if values.is_empty() {
+                    return Err(format!("Valor no encontrado en '{}'", line));
+                }
+                let column = |name: &str| columns.iter().position(|x| x.starts_with(name)).and_then(|i| values.get(i.checked_sub(2)?).copied());
+                let upper = column("ub").or_else(|| column("makespan")).unwrap_or(values[0]);
+                let lower = column("lb").unwrap_or(upper);
+                bounds.insert(format!("{}{}_{}", prefix, parameter, instance), Bound::new(lower, upper));
+            }
+            (Err(_), Some(Ok(upper))) => {
+                let lower = tokens.get(2).and_then(|x| value(x)).unwrap_or(upper);
+                bounds.insert(tokens[0].to_string(), Bound::new(lower, upper));
+            }
+            _ => {}
+        }
+    }
+    Ok(bounds)
+}
+
+/**
+* Return the prefix of instance names for a PSPLIB table, as j30 for j30opt.sm.
+*/
+fn table_prefix(filename: &str) -> String {
+    let name = std::path::Path::new(filename).file_name().map_or(String::new(), |x| x.to_string_lossy().to_string());
+    let letters : String = name.chars().take_while(|x| x.is_alphabetic()).collect();
+    let digits : String = name.chars().skip(letters.len()).take_while(|x| x.is_ascii_digit()).collect();
+    letters + &digits
+}
+
+/**
+* Parse a value of a table, which can have decimals as CPU times.
+*/
+fn value(token: &str) -> Option<u32> {
+    token.parse::<u32>().ok().or_else(|| token.parse::<f32>().ok().map(|x| x.round() as u32))
+}
+
+#[cfg(test)]
+ mod tests {
+     use crate::prcpsp::bounds as bounds;
+     use crate::prcpsp::bounds::Bound as Bound;
+
+     #[test]
+     fn test_parse_bounds() {
+         let table = "  Author: Example
+Paramter  Instance  Makespan  CPU-Time[sec]
+----------------------------------------------
+   1         1         43          1.20
+   1         2         47          0.45
+  48        10         43          2.10
+";
+         let table = bounds::parse_bounds(table, "j30").unwrap();
+         assert_eq!(table.len(), 3);
+         assert_eq!(table["j301_2"], Bound::new(47, 47));
+         assert_eq!(table["j3048_10"], Bound::new(43, 43));
+
+         let table = "Par  Inst   LB   UB
+  1    1     77   77
+  1    2     68   70
+";
+         let table = bounds::parse_bounds(table, "j60").unwrap();
+         assert_eq!(table["j601_2"], Bound::new(68, 70));
+         assert_eq!(table["j601_2"].gap(77.0), 10.0);
+
+         let table = "patterson-9 10\nj301_1 43 40\n";
+         let table = bounds::parse_bounds(table, "").unwrap();
+         assert_eq!(table["patterson-9"], Bound::new(10, 10));
+         assert_eq!(table["j301_1"], Bound::new(40, 43));
+         assert_eq!(table["j301_1"].gap_lower(42.0), 5.0);
+         assert_eq!(bounds::table_prefix("data/j120lb.sm"), "j120");
+     }
+ }
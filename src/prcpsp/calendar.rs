@@ -0,0 +1,81 @@
+use std::fs;
+
+use crate::prcpsp::project::Project as Project;
+
+/*
+* Calendar of resources, with the periods where the capacity of a resource changes.
+*
+* File format (lines starting with '#' are comments):
+*
+*   <resource> <start> <end> <capacity>
+*
+* where resource is the id or name of a renewable resource and the period
+* goes from start to end (not included). Later lines override earlier ones,
+* out of any period a resource has its constant capacity.
+*/
+
+/**
+* Read a calendar file and add its periods to the resources of a project.
+* project: project whose resources are changed.
+* filename: path to the calendar.
+*/
+pub fn read_calendar(project: &mut Project, filename: &str) -> Result<(), String> {
+    let contents = fs::read_to_string(filename)
+            .map_err(|e| format!("No se pudo leer el archivo {}: {}", filename, e))?;
+    parse_calendar(project, &contents)
+}
+
+/**
+* Add the periods of a calendar to the resources of a project.
+* project: project whose resources are changed.
+* contents: text of the calendar.
+*/
+pub fn parse_calendar(project: &mut Project, contents: &str) -> Result<(), String> {
+    for line in contents.lines().map(|x| x.trim()).filter(|x| !x.is_empty() && !x.starts_with('#')) {
+        let tokens : Vec<&str> = line.split_whitespace().collect();
+        if tokens.len() != 4 {
+            return Err(format!("Periodo incompleto: '{}'", line));
+        }
+        let start = tokens[1].parse::<i32>().map_err(|_| format!("Valor invalido '{}' en '{}'", tokens[1], line))?;
+        let end = tokens[2].parse::<i32>().map_err(|_| format!("Valor invalido '{}' en '{}'", tokens[2], line))?;
+        let capacity = tokens[3].parse::<u32>().map_err(|_| format!("Valor invalido '{}' en '{}'", tokens[3], line))?;
+        if start < 0 || end <= start {
+            return Err(format!("Periodo invalido en '{}'", line));
+        }
+        let resource = project.resources.iter_mut()
+                                        .find(|x| x.name == tokens[0] || x.id.to_string() == tokens[0])
+                                        .ok_or_else(|| format!("Recurso {} no encontrado", tokens[0]))?;
+        resource.add_period(start, end, capacity);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+ mod tests {
+     use crate::prcpsp::calendar as calendar;
+     use crate::prcpsp::psplib as psplib;
+     use crate::prcpsp::sa_state::SaState as SaState;
+     use crate::prcpsp::validator as validator;
+
+     #[test]
+     fn test_calendar() {
+         let mut project = psplib::read_sm("examples/psplib-9.sm").unwrap();
+         calendar::parse_calendar(&mut project, "# Mantenimiento\n1 2 4 1\n").unwrap();
+         let resource = &project.resources[0];
+         assert_eq!(resource.capacity_at(1), 5);
+         assert_eq!(resource.capacity_at(2), 1);
+         assert_eq!(resource.capacity_at(4), 5);
+
+         let state = SaState::new(project.clone(), 11);
+         assert!(validator::validate(&state.project, &state.planning, &state.times).is_empty());
+         assert!(state.get_makespan() > 10);
+
+         // Pieces of a pre-empted activity can stop during the period
+         let state = SaState::new(project.pre_emptive_project(3), 11);
+         assert!(validator::validate(&state.project, &state.planning, &state.times).is_empty());
+
+         assert!(calendar::parse_calendar(&mut project, "2 0 3 1\n").is_err());
+         assert!(calendar::parse_calendar(&mut project, "1 3 3 1\n").is_err());
+         assert!(calendar::parse_calendar(&mut project, "1 3 4\n").is_err());
+     }
+ }
@@ -0,0 +1,298 @@
+use std::fs;
+
+use crate::prcpsp::project::Project as Project;
+use crate::prcpsp::activity::Activity as Activity;
+use crate::prcpsp::rn_resource::RnResource as RnResource;
+use crate::prcpsp::rn_resource_usage::RnResourceUsage as RnResourceUsage;
+
+/**
+* Read a project from a CSV table of activities and a CSV table of resources.
+*
+* Activities table has a header with columns 'id', 'name', 'duration', 'predecessors'
+* and one column for the demand of each resource, named as the resource. Predecessors
+* are ids of other rows separated by ';', ',' or spaces. Optional columns 'release'
+* and 'deadline' have the time windows of activities, empty if an activity has none,
+* and optional columns 'interruptions', 'min_segment' and 'max_gap' the maximum interruptions
+* of each activity and the constraints on its segments, and optional column 'resume'
+* the time added to a subactivity resumed after an interruption. Optional columns 'weight',
+* 'due_date' and 'cash_flow' are used by the objectives, with a weight of 1, no due date
+* and no cash flow if they're empty.
+* Resources table has a header with columns 'name', 'capacity' and optionally 'id' and
+* 'cost', the unit cost of the capacity of a resource, 0 if it's empty.
+* Fields are separated by ',' or ';' and can be quoted.
+*
+* activities_file: path to the activities table.
+* resources_file: path to the resources table.
+*/
+pub fn read_csv(activities_file: &str, resources_file: &str) -> Result<Project, String> {
+    let activities = fs::read_to_string(activities_file)
+            .map_err(|e| format!("No se pudo leer el archivo {}: {}", activities_file, e))?;
+    let resources = fs::read_to_string(resources_file)
+            .map_err(|e| format!("No se pudo leer el archivo {}: {}", resources_file, e))?;
+    parse_csv(&activities, &resources)
+}
+
+/**
+* Return the default path of the resources table of an activities table,
+* <name>-resources.csv in the same directory.
+* activities_file: path to the activities table.
+*/
+pub fn resources_file(activities_file: &str) -> String {
+    let path = std::path::Path::new(activities_file);
+    let name = path.file_stem().map_or(String::new(), |x| x.to_string_lossy().to_string());
+    path.with_file_name(format!("{}-resources.csv", name)).to_string_lossy().to_string()
+}
+
+/**
+* Create a project from the contents of the activities and resources tables.
+* Activities get ids 2..n+1 in the order of rows, the dummy start activity
+* (id 1) and end activity (id n+2) are added.
+* activities: text of the activities table.
+* resources: text of the resources table.
+*/
+pub fn parse_csv(activities: &str, resources: &str) -> Result<Project, String> {
+    let resources = parse_resources(resources)?;
+
+    let rows = records(activities);
+    let header = rows.first().ok_or_else(|| String::from("Tabla de actividades vacia"))?;
+    let id_column = column(header, "id")?;
+    let name_column = column(header, "name").ok();
+    let duration_column = column(header, "duration")?;
+    let predecessors_column = column(header, "predecessors")?;
+    let release_column = column(header, "release").ok();
+    let deadline_column = column(header, "deadline").ok();
+    let interruptions_column = column(header, "interruptions").ok();
+    let min_segment_column = column(header, "min_segment").ok();
+    let max_gap_column = column(header, "max_gap").ok();
+    let resume_column = column(header, "resume").ok();
+    let weight_column = column(header, "weight").ok();
+    let due_date_column = column(header, "due_date").ok();
+    let cash_flow_column = column(header, "cash_flow").ok();
+    let demand_columns : Vec<(usize, &RnResource)> = resources.iter()
+                                                             .map(|r| column(header, &r.name).map(|c| (c, r)))
+                                                             .collect::<Result<Vec<(usize, &RnResource)>, String>>()?;
+
+    let mut keys : Vec<String> = vec![];
+    let mut activities : Vec<Activity> = vec![];
+    for (n, row) in rows.iter().enumerate().skip(1) {
+        let field = |c: usize| row.get(c).map_or("", |x| x.as_str());
+        let key = field(id_column).to_string();
+        if keys.contains(&key) {
+            return Err(format!("Fila {}: la actividad {} esta repetida", n + 1, key));
+        }
+        let id = activities.len() as u32 + 2;
+        let name = name_column.map_or(key.clone(), |c| field(c).to_string());
+        let duration = field(duration_column).parse::<u32>()
+                                             .map_err(|_| format!("Fila {}: duracion invalida '{}'", n + 1, field(duration_column)))?;
+        let mut supplies : Vec<RnResourceUsage> = vec![];
+        for (c, resource) in &demand_columns {
+            let usage = if field(*c).is_empty() { 0 } else {
+                field(*c).parse::<u32>().map_err(|_| format!("Fila {}: demanda invalida '{}'", n + 1, field(*c)))?
+            };
+            if usage > resource.capacity {
+                return Err(format!("Fila {}: la demanda {} del recurso {} es mayor a su capacidad", n + 1, usage, resource.name));
+            }
+            if usage > 0 {
+                supplies.push(RnResourceUsage::new((*resource).clone(), usage));
+            }
+        }
+        let number = |c: Option<usize>, default: i32| match c.map(field).filter(|x| !x.is_empty()) {
+            Some(value) => value.parse::<u32>().map(|x| x as i32).map_err(|_| format!("Fila {}: valor invalido '{}'", n + 1, value)),
+            None => Ok(default),
+        };
+        let mut activity = Activity::new(id, -1, name, vec![], vec![], supplies, duration, -1);
+        activity.release = number(release_column, 0)?;
+        activity.deadline = number(deadline_column, -1)?;
+        activity.max_interruptions = number(interruptions_column, -1)?;
+        activity.min_segment = number(min_segment_column, 0)? as u32;
+        activity.max_gap = number(max_gap_column, -1)?;
+        activity.resume_time = number(resume_column, 0)? as u32;
+        activity.weight = number(weight_column, 1)? as u32;
+        activity.due_date = number(due_date_column, -1)?;
+        activity.cash_flow = match cash_flow_column.map(field).filter(|x| !x.is_empty()) {
+            Some(value) => value.parse::<i32>().map_err(|_| format!("Fila {}: valor invalido '{}'", n + 1, value))?,
+            None => 0,
+        };
+        if activity.deadline >= 0 && activity.deadline < activity.release + activity.shortest_duration() as i32 {
+            return Err(format!("Fila {}: la fecha limite es anterior a la liberacion mas la duracion", n + 1));
+        }
+        keys.push(key);
+        activities.push(activity);
+    }
+
+    for (n, row) in rows.iter().enumerate().skip(1) {
+        let field = row.get(predecessors_column).map_or("", |x| x.as_str());
+        for key in field.split(|x: char| x == ';' || x == ',' || x.is_whitespace()).filter(|x| !x.is_empty()) {
+            let i = keys.iter().position(|x| x == key)
+                        .ok_or_else(|| format!("Fila {}: predecesor {} no encontrado", n + 1, key))?;
+            let (id, successor) = (activities[i].id, activities[n - 1].id);
+            activities[n - 1].add_anteccessor(id);
+            activities[i].add_successor(successor);
+        }
+    }
+    check_cycles(&activities, &keys)?;
+
+    let n = activities.len() as u32;
+    let mut start = Activity::new(1, -1, String::from("inicio"), vec![], vec![], vec![], 0, -1);
+    let mut end = Activity::new(n + 2, -1, String::from("fin"), vec![], vec![], vec![], 0, -1);
+    for activity in &mut activities {
+        if activity.predecessors.is_empty() {
+            activity.add_anteccessor(start.id);
+            start.add_successor(activity.id);
+        }
+        if activity.successors.is_empty() {
+            activity.add_successor(end.id);
+            end.add_anteccessor(activity.id);
+        }
+    }
+    activities.insert(0, start);
+    activities.push(end);
+    Ok(Project::new(activities, resources))
+}
+
+/**
+* Create the resources from the contents of the resources table.
+*/
+fn parse_resources(contents: &str) -> Result<Vec<RnResource>, String> {
+    let rows = records(contents);
+    let header = rows.first().ok_or_else(|| String::from("Tabla de recursos vacia"))?;
+    let id_column = column(header, "id").ok();
+    let name_column = column(header, "name")?;
+    let capacity_column = column(header, "capacity")?;
+    let cost_column = column(header, "cost").ok();
+    let mut resources : Vec<RnResource> = vec![];
+    for (n, row) in rows.iter().enumerate().skip(1) {
+        let field = |c: usize| row.get(c).map_or("", |x| x.as_str());
+        let id = match id_column {
+            Some(c) => field(c).parse::<u32>().map_err(|_| format!("Fila {}: id de recurso invalido '{}'", n + 1, field(c)))?,
+            None => n as u32,
+        };
+        let capacity = field(capacity_column).parse::<u32>()
+                                             .map_err(|_| format!("Fila {}: capacidad invalida '{}'", n + 1, field(capacity_column)))?;
+        let cost = match cost_column.map(field).filter(|x| !x.is_empty()) {
+            Some(value) => value.parse::<u32>().map_err(|_| format!("Fila {}: costo invalido '{}'", n + 1, value))?,
+            None => 0,
+        };
+        let name = field(name_column).to_string();
+        if resources.iter().any(|x| x.id == id || x.name == name) {
+            return Err(format!("Fila {}: el recurso {} esta repetido", n + 1, name));
+        }
+        let mut resource = RnResource::new(id, name, capacity);
+        resource.cost = cost;
+        resources.push(resource);
+    }
+    Ok(resources)
+}
+
+/**
+* Return an error if precedences between activities have a cycle.
+*/
+fn check_cycles(activities: &[Activity], keys: &[String]) -> Result<(), String> {
+    let mut planned : Vec<u32> = vec![];
+    while planned.len() != activities.len() {
+        let ready : Vec<u32> = activities.iter()
+                                         .filter(|x| !planned.contains(&x.id) && x.predecessors.iter().all(|p| planned.contains(p)))
+                                         .map(|x| x.id)
+                                         .collect();
+        if ready.is_empty() {
+            let cycle : Vec<&str> = activities.iter()
+                                              .zip(keys.iter())
+                                              .filter(|(x, _)| !planned.contains(&x.id))
+                                              .map(|(_, k)| k.as_str())
+                                              .collect();
+            return Err(format!("Las actividades {} tienen precedencias ciclicas", cycle.join(", ")));
+        }
+        planned.extend(ready);
+    }
+    Ok(())
+}
+
+/**
+* Return the position of a column in header.
+*/
+fn column(header: &[String], name: &str) -> Result<usize, String> {
+    header.iter()
+          .position(|x| x.eq_ignore_ascii_case(name))
+          .ok_or_else(|| format!("Columna '{}' no encontrada", name))
+}
+
+/**
+* Split a CSV text in records of fields, skipping empty lines.
+* The separator is ';' if the first line has it and doesn't have ',', otherwise ','.
+*/
+fn records(contents: &str) -> Vec<Vec<String>> {
+    let first = contents.lines().next().unwrap_or("");
+    let separator = if first.contains(';') && !first.contains(',') { ';' } else { ',' };
+    contents.lines()
+            .filter(|x| !x.trim().is_empty())
+            .map(|line| {
+                let mut fields : Vec<String> = vec![];
+                let mut field = String::new();
+                let mut quoted = false;
+                let mut chars = line.chars().peekable();
+                while let Some(c) = chars.next() {
+                    if c == '"' && quoted && chars.peek() == Some(&'"') {
+                        field.push('"');
+                        chars.next();
+                    } else if c == '"' {
+                        quoted = !quoted;
+                    } else if c == separator && !quoted {
+                        fields.push(field.trim().to_string());
+                        field.clear();
+                    } else {
+                        field.push(c);
+                    }
+                }
+                fields.push(field.trim().to_string());
+                fields
+            })
+            .collect()
+}
+
+#[cfg(test)]
+ mod tests {
+     use crate::prcpsp::csv_project as csv_project;
+     use crate::prcpsp::sa_state::SaState as SaState;
+
+     const RESOURCES : &str = "id,name,capacity\n1,crew,5\n";
+     const ACTIVITIES : &str = "id,name,duration,predecessors,crew
+A,Excavation,1,,1
+B,Foundation,2,,2
+C,Walls,4,,2
+D,Roof,3,,2
+E,Windows,1,A,2
+F,Paint,5,E,1
+G,\"Electric, plumbing\",3,D,2
+";
+
+     #[test]
+     fn test_parse_csv() {
+         let project = csv_project::parse_csv(ACTIVITIES, RESOURCES).unwrap();
+         assert_eq!(project.activities.len(), 9);
+         assert_eq!(project.activities[0].successors, vec![2,3,4,5]);
+         assert_eq!(project.activities[8].predecessors, vec![3,4,7,8]);
+         assert_eq!(project.activities[7].name, "Electric, plumbing");
+         let state = SaState::new(project, 11);
+         assert_eq!(state.planning, vec![1,2,3,4,5,6,8,7,9]);
+         assert_eq!(state.times, vec![0,0,0,0,2,4,5,5,10]);
+
+         let semicolon = ACTIVITIES.replace(',', ";").replace("\"Electric; plumbing\"", "Electric");
+         let project = csv_project::parse_csv(&semicolon, RESOURCES).unwrap();
+         assert_eq!(project.activities.len(), 9);
+     }
+
+     #[test]
+     fn test_parse_csv_errors() {
+         assert!(csv_project::parse_csv(&ACTIVITIES.replace("E,Windows,1,A", "E,Windows,1,X"), RESOURCES).is_err());
+         assert!(csv_project::parse_csv(&ACTIVITIES.replace("A,Excavation,1,", "A,Excavation,1,F"), RESOURCES).is_err());
+         assert!(csv_project::parse_csv(&ACTIVITIES.replace("B,Foundation,2,,2", "B,Foundation,2,,9"), RESOURCES).is_err());
+         assert!(csv_project::parse_csv(ACTIVITIES, "name,units\ncrew,5\n").is_err());
+         assert!(csv_project::parse_csv(ACTIVITIES, "name,capacity\ncrew,5\ncrane,1\n").is_err());
+     }
+
+     #[test]
+     fn test_resources_file() {
+         assert_eq!(csv_project::resources_file("examples/project.csv"), "examples/project-resources.csv");
+         assert_eq!(csv_project::resources_file("data.csv/house.csv"), "data.csv/house-resources.csv");
+     }
+ }
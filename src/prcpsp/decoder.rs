@@ -0,0 +1,91 @@
+use std::fmt;
+
+use rand::{Rng, SeedableRng, StdRng};
+
+/**
+* Schedule generation scheme that turns an activity list into start times.
+*/
+#[derive(Debug,Clone,Copy,PartialEq)]
+pub enum Decoder {
+    /* Serial: each activity in list order at the earliest feasible time from the start of the previous one. */
+    Serial,
+    /* Parallel: at each time, the eligible activities in list order that fit (non-delay schedules). */
+    Parallel,
+    /* Both for every activity list, keeping the schedule of lower cost. */
+    Hybrid,
+}
+
+impl Decoder {
+    /**
+    * Return the decoder from its name: serial, parallel or hybrid.
+    */
+    pub fn from_str(value: &str) -> Option<Decoder> {
+        match value.to_lowercase().as_str() {
+            "serial" => Some(Decoder::Serial),
+            "parallel" => Some(Decoder::Parallel),
+            "hybrid" => Some(Decoder::Hybrid),
+            _ => None,
+        }
+    }
+
+    /**
+    * Return the serial or the parallel decoder, chosen at random for a run.
+    * seed: seed of the run.
+    */
+    pub fn for_run(seed: u64) -> Decoder {
+        let mut rng : StdRng = SeedableRng::seed_from_u64(seed);
+        if rng.gen::<bool>() { Decoder::Parallel } else { Decoder::Serial }
+    }
+}
+
+impl fmt::Display for Decoder {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Decoder::Serial => write!(f, "serial"),
+            Decoder::Parallel => write!(f, "parallel"),
+            Decoder::Hybrid => write!(f, "hybrid"),
+        }
+    }
+}
+
+#[cfg(test)]
+ mod tests {
+     use crate::prcpsp::decoder::Decoder as Decoder;
+     use crate::prcpsp::csv_project as csv_project;
+     use crate::prcpsp::psplib as psplib;
+     use crate::prcpsp::sa_state::SaState as SaState;
+     use crate::prcpsp::validator as validator;
+     use crate::metaheuristics::state::State as State;
+
+     #[test]
+     fn test_decoders() {
+         let resources = "name,capacity\ncrew,2\n";
+         let activities = "id,name,duration,predecessors,crew\nA,Pour,3,,1\nB,Cure,1,A,1\nC,Paint,3,,1\n";
+         let project = csv_project::parse_csv(activities, resources).unwrap();
+
+         // Serial starts Paint after Cure, parallel starts it while the crew isn't busy
+         let mut state = SaState::from_planning(project.pre_emptive_project(0), vec![1,2,3,4,5], 14).unwrap();
+         assert_eq!(state.times, vec![0,0,3,3,6]);
+         state.set_decoder(Decoder::Parallel);
+         assert_eq!(state.times, vec![0,0,3,0,4]);
+         assert_eq!(state.get_makespan(), 4);
+         state.set_decoder(Decoder::Hybrid);
+         assert_eq!(state.times, vec![0,0,3,0,4]);
+         assert!(validator::validate(&state.project, &state.planning, &state.times).is_empty());
+
+         let project = psplib::read_sm("examples/psplib-9.sm").unwrap().pre_emptive_project(2);
+         for decoder in &[Decoder::Serial, Decoder::Parallel, Decoder::Hybrid] {
+             let mut state = SaState::new(project.clone(), 14);
+             state.set_decoder(*decoder);
+             assert!(validator::validate(&state.project, &state.planning, &state.times).is_empty());
+             let (neighbor_cost, movement, _) = State::get_neighbor(&mut state);
+             state.set_neighbor(movement);
+             assert_eq!(state.get_cost(), neighbor_cost);
+             assert!(validator::validate(&state.project, &state.planning, &state.times).is_empty());
+         }
+
+         assert_eq!(Decoder::from_str("Parallel"), Some(Decoder::Parallel));
+         assert_eq!(Decoder::from_str("random"), None);
+         assert_eq!(Decoder::for_run(14), Decoder::for_run(14));
+     }
+ }
@@ -0,0 +1,514 @@
+use std::fs;
+
+use crate::prcpsp::project::Project as Project;
+use crate::prcpsp::activity::Activity as Activity;
+use crate::prcpsp::rn_resource::RnResource as RnResource;
+use crate::prcpsp::rn_resource_usage::RnResourceUsage as RnResourceUsage;
+use crate::prcpsp::nr_resource::NrResource as NrResource;
+use crate::prcpsp::nr_resource_usage::NrResourceUsage as NrResourceUsage;
+use crate::prcpsp::relation as relation;
+use crate::prcpsp::window as window;
+use crate::prcpsp::preemption as preemption;
+use crate::prcpsp::objective as objective;
+use crate::prcpsp::worker as worker;
+use crate::prcpsp::stochastic as stochastic;
+use crate::prcpsp::availability as availability;
+
+/**
+* Text format for instances (.prj files).
+*
+* Lines starting with '#' are comments and empty lines are ignored.
+*
+*   resources <k>
+*   <id> <capacity> <name>                       (k lines)
+*   nonrenewable <l>                             (optional)
+*   <id> <capacity> <name>                       (l lines)
+*   activities <n>
+*   <id> <parent> <duration> <start_time> <usage_1> .. <usage_k> <consumption_1> .. <consumption_l> <successors> <s_1> .. <s_m> <name>   (n lines)
+*   relations <r>                                (optional)
+*   <from> <to> <type> <min_lag> [<max_lag>]     (r lines)
+*   windows <w>                                  (optional)
+*   <id> <release> <deadline>                    (w lines)
+*   interruptions <i>                            (optional)
+*   <id> <max_interruptions> <min_segment> <max_gap>   (i lines)
+*   resume <o>                                   (optional)
+*   <id> <resume_time> [<resource> <usage> ..]   (o lines)
+*   costs <c>                                    (optional)
+*   <id> <weight> <due_date> <cash_flow>         (c lines)
+*   workers <w>                                  (optional)
+*   <name> <resource> <level> [<resource> <level> ..]   (w lines)
+*   skills <s>                                   (optional)
+*   <id> <resource> <usage> <level>              (s lines)
+*   durations <d>                                (optional)
+*   <id> <distribution> <value_1> .. <value_v>   (d lines)
+*   prices <p>                                   (optional)
+*   <id> <unit_cost>                             (p lines)
+*
+* Usages and consumptions follow the order of the resources, start_time is -1 if the activity is not
+* planned, parent is -1 if the activity isn't a subactivity and deadline is -1 if
+* the activity doesn't have one. Activities without maximum interruptions use the number
+* of interruptions of the project, min_segment is 0 and max_gap -1 if the segments of an
+* activity don't have constraints. The resume overhead of an activity is its time and
+* the extra usage of resources (by id) after an interruption. The weight, due date (-1 if
+* there isn't one) and cash flow of activities are used by the objectives. Workers have a
+* level in each of their skills, resources (by id) whose capacity is the number of workers
+* with them, and activities need workers with at least the level of their skills. Distributions
+* of durations are triangular, pert or empirical, as in duration files. Prices are the unit
+* costs of the capacity of resources (by id), chosen by the search when it minimizes their cost. First and last
+* activities are the dummy source and sink.
+*/
+const HEADER : &str = "# Ejemplar PRCPSP
+# resources <k>
+# <id> <capacity> <name>
+# nonrenewable <l>
+# <id> <capacity> <name>
+# activities <n>
+# <id> <parent> <duration> <start_time> <usage_1> .. <usage_k> <consumption_1> .. <consumption_l> <successors> <s_1> .. <s_m> <name>
+# relations <r>
+# <from> <to> <type> <min_lag> [<max_lag>]
+# windows <w>
+# <id> <release> <deadline>
+# interruptions <i>
+# <id> <max_interruptions> <min_segment> <max_gap>
+# resume <o>
+# <id> <resume_time> [<resource> <usage> ..]
+# costs <c>
+# <id> <weight> <due_date> <cash_flow>
+# workers <w>
+# <name> <resource> <level> [<resource> <level> ..]
+# skills <s>
+# <id> <resource> <usage> <level>
+# durations <d>
+# <id> <distribution> <value_1> .. <value_v>
+# prices <p>
+# <id> <unit_cost>
+";
+
+/**
+* Return the project in the text format for instances.
+* project: project to write.
+*/
+pub fn to_string(project: &Project) -> String {
+    let mut content = String::from(HEADER);
+    content.push_str(&format!("resources {}\n", project.resources.len()));
+    for r in &project.resources {
+        content.push_str(&format!("{} {} {}\n", r.id, r.capacity, r.name));
+    }
+    if !project.nr_resources.is_empty() {
+        content.push_str(&format!("nonrenewable {}\n", project.nr_resources.len()));
+        for r in &project.nr_resources {
+            content.push_str(&format!("{} {} {}\n", r.id, r.capacity, r.name));
+        }
+    }
+    content.push_str(&format!("activities {}\n", project.activities.len()));
+    for a in &project.activities {
+        content.push_str(&format!("{} {} {} {}", a.id, a.parent, a.duration, a.start_time));
+        for r in &project.resources {
+            let usage = a.supplies.iter().find(|x| x.resource == *r).map_or(0, |x| x.usage);
+            content.push_str(&format!(" {}", usage));
+        }
+        for r in &project.nr_resources {
+            let consumption = a.consumptions.iter().find(|x| x.resource == *r).map_or(0, |x| x.usage);
+            content.push_str(&format!(" {}", consumption));
+        }
+        content.push_str(&format!(" {}", a.successors.len()));
+        for s in &a.successors {
+            content.push_str(&format!(" {}", s));
+        }
+        content.push_str(&format!(" {}\n", a.name));
+    }
+    if !project.relations.is_empty() {
+        content.push_str(&format!("relations {}\n", project.relations.len()));
+        for r in &project.relations {
+            content.push_str(&format!("{} {} {} {}", r.from, r.to, r.kind, r.min_lag));
+            if let Some(max_lag) = r.max_lag {
+                content.push_str(&format!(" {}", max_lag));
+            }
+            content.push('\n');
+        }
+    }
+    let windows : Vec<&Activity> = project.activities.iter().filter(|x| x.release > 0 || x.deadline >= 0).collect();
+    if !windows.is_empty() {
+        content.push_str(&format!("windows {}\n", windows.len()));
+        for a in windows {
+            content.push_str(&format!("{} {} {}\n", a.id, a.release, a.deadline));
+        }
+    }
+    let interruptions : Vec<&Activity> = project.activities.iter()
+                                                   .filter(|x| x.max_interruptions >= 0 || x.min_segment > 0 || x.max_gap >= 0)
+                                                   .collect();
+    if !interruptions.is_empty() {
+        content.push_str(&format!("interruptions {}\n", interruptions.len()));
+        for a in interruptions {
+            content.push_str(&format!("{} {} {} {}\n", a.id, a.max_interruptions, a.min_segment, a.max_gap));
+        }
+    }
+    let resume : Vec<&Activity> = project.activities.iter().filter(|x| x.resume_time > 0 || !x.resume_supplies.is_empty()).collect();
+    if !resume.is_empty() {
+        content.push_str(&format!("resume {}\n", resume.len()));
+        for a in resume {
+            content.push_str(&format!("{} {}", a.id, a.resume_time));
+            for s in &a.resume_supplies {
+                content.push_str(&format!(" {} {}", s.resource.id, s.usage));
+            }
+            content.push('\n');
+        }
+    }
+    let costs : Vec<&Activity> = project.activities.iter().filter(|x| x.weight != 1 || x.due_date >= 0 || x.cash_flow != 0).collect();
+    if !costs.is_empty() {
+        content.push_str(&format!("costs {}\n", costs.len()));
+        for a in costs {
+            content.push_str(&format!("{} {} {} {}\n", a.id, a.weight, a.due_date, a.cash_flow));
+        }
+    }
+    if !project.workers.is_empty() {
+        content.push_str(&format!("workers {}\n", project.workers.len()));
+        for w in &project.workers {
+            content.push_str(&w.name);
+            for (resource, level) in &w.skills {
+                content.push_str(&format!(" {} {}", resource, level));
+            }
+            content.push('\n');
+        }
+    }
+    let skills : Vec<(u32, &RnResourceUsage)> = project.activities.iter()
+                                                           .flat_map(|a| a.supplies.iter().filter(|x| x.level > 0).map(move |x| (a.id, x)))
+                                                           .collect();
+    if !skills.is_empty() {
+        content.push_str(&format!("skills {}\n", skills.len()));
+        for (id, s) in skills {
+            content.push_str(&format!("{} {} {} {}\n", id, s.resource.id, s.usage, s.level));
+        }
+    }
+    let durations : Vec<&Activity> = project.activities.iter().filter(|x| x.distribution.is_some()).collect();
+    if !durations.is_empty() {
+        content.push_str(&format!("durations {}\n", durations.len()));
+        for a in durations {
+            content.push_str(&format!("{} {}\n", a.id, a.distribution.as_ref().unwrap()));
+        }
+    }
+    let prices : Vec<&RnResource> = project.resources.iter().filter(|x| x.cost > 0).collect();
+    if !prices.is_empty() {
+        content.push_str(&format!("prices {}\n", prices.len()));
+        for r in prices {
+            content.push_str(&format!("{} {}\n", r.id, r.cost));
+        }
+    }
+    content
+}
+
+/**
+* Write the project in the text format for instances.
+* project: project to write.
+* filename: path of the file.
+*/
+pub fn write_instance(project: &Project, filename: &str) -> Result<(), String> {
+    fs::write(filename, to_string(project).as_bytes())
+        .map_err(|e| format!("No se pudo escribir el archivo {}: {}", filename, e))
+}
+
+/**
+* Read an instance in the text format for instances.
+* filename: path to the instance.
+*/
+pub fn read_instance(filename: &str) -> Result<Project, String> {
+    let contents = fs::read_to_string(filename)
+            .map_err(|e| format!("No se pudo leer el archivo {}: {}", filename, e))?;
+    parse_instance(&contents)
+}
+
+/**
+* Create a project from the contents of a file in the text format for instances.
+* contents: text of the instance.
+*/
+pub fn parse_instance(contents: &str) -> Result<Project, String> {
+    let mut lines = contents.lines()
+                            .map(|x| x.trim())
+                            .filter(|x| !x.is_empty() && !x.starts_with('#'))
+                            .peekable();
+
+    let k = section_size(lines.next(), "resources")?;
+    let mut resources : Vec<RnResource> = vec![];
+    for _ in 0..k {
+        let line = lines.next().ok_or_else(|| String::from("Faltan recursos"))?;
+        let mut tokens = line.split_whitespace();
+        let id = number::<u32>(tokens.next(), line)?;
+        let capacity = number::<u32>(tokens.next(), line)?;
+        let name = tokens.collect::<Vec<&str>>().join(" ");
+        let name = if name.is_empty() { id.to_string() } else { name };
+        resources.push(RnResource::new(id, name, capacity));
+    }
+
+    let mut nr_resources : Vec<NrResource> = vec![];
+    if lines.peek().is_some_and(|x| x.starts_with("nonrenewable")) {
+        let l = section_size(lines.next(), "nonrenewable")?;
+        for _ in 0..l {
+            let line = lines.next().ok_or_else(|| String::from("Faltan recursos no renovables"))?;
+            let mut tokens = line.split_whitespace();
+            let id = number::<u32>(tokens.next(), line)?;
+            let capacity = number::<u32>(tokens.next(), line)?;
+            let name = tokens.collect::<Vec<&str>>().join(" ");
+            let name = if name.is_empty() { id.to_string() } else { name };
+            nr_resources.push(NrResource::new(id, name, capacity));
+        }
+    }
+    let columns = resources.len() + nr_resources.len();
+
+    let n = section_size(lines.next(), "activities")?;
+    let mut activities : Vec<Activity> = vec![];
+    for _ in 0..n {
+        let line = lines.next().ok_or_else(|| String::from("Faltan actividades"))?;
+        let tokens : Vec<&str> = line.split_whitespace().collect();
+        if tokens.len() < columns + 5 {
+            return Err(format!("Actividad incompleta: '{}'", line));
+        }
+        let id = number::<u32>(tokens.first().copied(), line)?;
+        let parent = number::<i32>(tokens.get(1).copied(), line)?;
+        let duration = number::<u32>(tokens.get(2).copied(), line)?;
+        let start_time = number::<i32>(tokens.get(3).copied(), line)?;
+        let mut supplies : Vec<RnResourceUsage> = vec![];
+        for (j, r) in resources.iter().enumerate() {
+            let usage = number::<u32>(tokens.get(4 + j).copied(), line)?;
+            if usage > 0 {
+                supplies.push(RnResourceUsage::new(r.clone(), usage));
+            }
+        }
+        let mut consumptions : Vec<NrResourceUsage> = vec![];
+        for (j, r) in nr_resources.iter().enumerate() {
+            let consumption = number::<u32>(tokens.get(4 + k + j).copied(), line)?;
+            if consumption > 0 {
+                consumptions.push(NrResourceUsage::new(r.clone(), consumption));
+            }
+        }
+        let count = number::<usize>(tokens.get(4 + columns).copied(), line)?;
+        let mut successors : Vec<u32> = vec![];
+        for j in 0..count {
+            successors.push(number::<u32>(tokens.get(5 + columns + j).copied(), line)?);
+        }
+        let name = tokens[5 + columns + count..].join(" ");
+        let name = if name.is_empty() { id.to_string() } else { name };
+        let mut activity = Activity::new(id, parent, name, vec![], successors, supplies, duration, start_time);
+        activity.consumptions = consumptions;
+        activities.push(activity);
+    }
+
+    let links : Vec<(u32, u32)> = activities.iter().flat_map(|a| a.successors.iter().map(move |s| (a.id, *s))).collect();
+    for (id, s) in links {
+        match activities.iter_mut().find(|x| x.id == s) {
+            Some(successor) => successor.add_anteccessor(id),
+            None => return Err(format!("Sucesor {} de la actividad {} no encontrado", s, id)),
+        }
+    }
+    let mut project = Project::new(activities, resources);
+    project.nr_resources = nr_resources;
+    // Optional sections, in any order
+    while let Some(line) = lines.next() {
+        let section = line.split_whitespace().next().unwrap_or("");
+        let size = section_size(Some(line), section)?;
+        let contents : Vec<&str> = lines.by_ref().take(size).collect();
+        if contents.len() != size {
+            return Err(format!("Faltan lineas en la seccion '{}'", section));
+        }
+        match section {
+            "relations" => relation::parse_relations(&mut project, &contents.join("\n"))?,
+            "windows" => window::parse_windows(&mut project, &contents.join("\n"))?,
+            "interruptions" => preemption::parse_interruptions(&mut project, &contents.join("\n"))?,
+            "resume" => preemption::parse_resume(&mut project, &contents.join("\n"))?,
+            "costs" => objective::parse_costs(&mut project, &contents.join("\n"))?,
+            "workers" => worker::parse_workers(&mut project, &contents.join("\n"))?,
+            "skills" => worker::parse_skills(&mut project, &contents.join("\n"))?,
+            "durations" => stochastic::parse_distributions(&mut project, &contents.join("\n"))?,
+            "prices" => availability::parse_prices(&mut project, &contents.join("\n"))?,
+            _ => return Err(format!("Seccion desconocida '{}'", line)),
+        }
+    }
+    Ok(project)
+}
+
+/**
+* Return the project in PSPLIB single-mode format.
+* Activities should have ids 1..n in order, as in projects read or generated.
+* project: project to write.
+*/
+pub fn to_sm(project: &Project) -> String {
+    let line = "************************************************************************\n";
+    let jobs = project.activities.len();
+    let horizon : u32 = project.activities.iter().map(|x| x.duration).sum();
+    let k = project.resources.len();
+    let l = project.nr_resources.len();
+    let mut content = String::from(line);
+    content.push_str("file with basedata            : ts-sa-prcpsp\n");
+    content.push_str("initial value random generator: 0\n");
+    content.push_str(line);
+    content.push_str("projects                      :  1\n");
+    content.push_str(&format!("jobs (incl. supersource/sink ):  {}\n", jobs));
+    content.push_str(&format!("horizon                       :  {}\n", horizon));
+    content.push_str("RESOURCES\n");
+    content.push_str(&format!("  - renewable                 :  {}   R\n", k));
+    content.push_str(&format!("  - nonrenewable              :  {}   N\n", l));
+    content.push_str("  - doubly constrained        :  0   D\n");
+    content.push_str(line);
+    content.push_str("PRECEDENCE RELATIONS:\n");
+    content.push_str("jobnr.    #modes  #successors   successors\n");
+    for a in &project.activities {
+        content.push_str(&format!("{:>4}        1{:>11}        ", a.id, a.successors.len()));
+        for s in &a.successors {
+            content.push_str(&format!("{:>4}", s));
+        }
+        content.push('\n');
+    }
+    content.push_str(line);
+    content.push_str("REQUESTS/DURATIONS:\n");
+    content.push_str("jobnr. mode duration");
+    for r in 1..=k {
+        content.push_str(&format!("  R{:>2}", r));
+    }
+    for r in 1..=l {
+        content.push_str(&format!("  N{:>2}", r));
+    }
+    content.push('\n');
+    content.push_str("------------------------------------------------------------------------\n");
+    for a in &project.activities {
+        content.push_str(&format!("{:>3}{:>7}{:>6}    ", a.id, 1, a.duration));
+        for r in &project.resources {
+            let usage = a.supplies.iter().find(|x| x.resource == *r).map_or(0, |x| x.usage);
+            content.push_str(&format!("{:>5}", usage));
+        }
+        for r in &project.nr_resources {
+            let consumption = a.consumptions.iter().find(|x| x.resource == *r).map_or(0, |x| x.usage);
+            content.push_str(&format!("{:>5}", consumption));
+        }
+        content.push('\n');
+    }
+    content.push_str(line);
+    content.push_str("RESOURCEAVAILABILITIES:\n");
+    for r in 1..=k {
+        content.push_str(&format!("  R{:>2}", r));
+    }
+    for r in 1..=l {
+        content.push_str(&format!("  N{:>2}", r));
+    }
+    content.push('\n');
+    for r in &project.resources {
+        content.push_str(&format!("{:>5}", r.capacity));
+    }
+    for r in &project.nr_resources {
+        content.push_str(&format!("{:>5}", r.capacity));
+    }
+    content.push('\n');
+    content.push_str(line);
+    content
+}
+
+/**
+* Write the project in PSPLIB single-mode format.
+* project: project to write.
+* filename: path of the file.
+*/
+pub fn write_sm(project: &Project, filename: &str) -> Result<(), String> {
+    fs::write(filename, to_sm(project).as_bytes())
+        .map_err(|e| format!("No se pudo escribir el archivo {}: {}", filename, e))
+}
+
+/**
+* Return the size of a section from its header as 'activities 9'.
+*/
+pub(crate) fn section_size(line: Option<&str>, section: &str) -> Result<usize, String> {
+    let line = line.ok_or_else(|| format!("Seccion '{}' no encontrada", section))?;
+    let mut tokens = line.split_whitespace();
+    if tokens.next() != Some(section) {
+        return Err(format!("Se esperaba la seccion '{}' en '{}'", section, line));
+    }
+    number::<usize>(tokens.next(), line)
+}
+
+/**
+* Parse a token as a number.
+*/
+pub(crate) fn number<T: std::str::FromStr>(token: Option<&str>, line: &str) -> Result<T, String> {
+    match token {
+        Some(t) => t.parse::<T>().map_err(|_| format!("Valor invalido '{}' en '{}'", t, line)),
+        None => Err(format!("Valores faltantes en '{}'", line)),
+    }
+}
+
+#[cfg(test)]
+ mod tests {
+     use crate::prcpsp::instance as instance;
+     use crate::prcpsp::psplib as psplib;
+     use crate::prcpsp::random_rcpsp as random_rcpsp;
+     use crate::prcpsp::relation as relation;
+     use crate::prcpsp::window as window;
+     use crate::prcpsp::preemption as preemption;
+     use crate::prcpsp::objective as objective;
+
+     #[test]
+     fn test_write_instance() {
+         let project = random_rcpsp::get_random_rcpsp(14, 30, 10, 5, 20);
+         let other = instance::parse_instance(&instance::to_string(&project)).unwrap();
+         assert_eq!(project.resources.len(), other.resources.len());
+         assert_eq!(project.activities.len(), other.activities.len());
+         for (a, b) in project.activities.iter().zip(other.activities.iter()) {
+             assert_eq!(a.id, b.id);
+             assert_eq!(a.duration, b.duration);
+             assert_eq!(a.start_time, b.start_time);
+             assert_eq!(a.predecessors, b.predecessors);
+             assert_eq!(a.successors, b.successors);
+             assert_eq!(a.get_demand(), b.get_demand());
+         }
+
+         let r = &project.resources[0];
+         let spaced = instance::to_string(&project).replacen(&format!("{} {} {}\n", r.id, r.capacity, r.name),
+                                                            &format!("{}  {}\t{}\n", r.id, r.capacity, r.name), 1);
+         let other = instance::parse_instance(&spaced).unwrap();
+         assert_eq!((other.resources[0].capacity, &other.resources[0].name), (r.capacity, &r.name));
+
+         let mut pre_emptive = project.pre_emptive_project(2);
+         relation::parse_relations(&mut pre_emptive, "2 5 SS 1\n3 4 FF -1 2\n").unwrap();
+         let other = instance::parse_instance(&instance::to_string(&pre_emptive)).unwrap();
+         for (a, b) in pre_emptive.activities.iter().zip(other.activities.iter()) {
+             assert_eq!(a.parent, b.parent);
+         }
+         assert_eq!(pre_emptive.relations, other.relations);
+
+         let mut project = project;
+         window::parse_windows(&mut project, "3 2 500\n5 4\n").unwrap();
+         preemption::parse_interruptions(&mut project, "3 0\n4 5 2 3\n").unwrap();
+         preemption::parse_resume(&mut project, "4 2 1 0\n5 0\n").unwrap();
+         objective::parse_costs(&mut project, "3 2 10 -5\n6 1 -1 20\n").unwrap();
+         let other = instance::parse_instance(&instance::to_string(&project)).unwrap();
+         for (a, b) in project.activities.iter().zip(other.activities.iter()) {
+             assert_eq!((a.release, a.deadline), (b.release, b.deadline));
+             assert_eq!((a.max_interruptions, a.min_segment, a.max_gap), (b.max_interruptions, b.min_segment, b.max_gap));
+             assert_eq!((a.resume_time, a.resume_supplies.len()), (b.resume_time, b.resume_supplies.len()));
+             assert_eq!((a.weight, a.due_date, a.cash_flow), (b.weight, b.due_date, b.cash_flow));
+         }
+     }
+
+     #[test]
+     fn test_write_nr_resources() {
+         let mut project = random_rcpsp::get_random_rcpsp(14, 30, 10, 5, 20);
+         random_rcpsp::add_nr_resources(&mut project, 14, 2);
+         let other = instance::parse_instance(&instance::to_string(&project)).unwrap();
+         let sm = psplib::parse_sm(&instance::to_sm(&project)).unwrap();
+         for p in &[other, sm] {
+             assert_eq!(p.nr_resources.len(), 2);
+             assert_eq!(p.nr_resources[1].capacity, project.nr_resources[1].capacity);
+             assert_eq!(p.nr_consumption()[0].1, project.nr_consumption()[0].1);
+             assert_eq!(p.nr_excess(), 0);
+         }
+     }
+
+     #[test]
+     fn test_write_sm() {
+         let project = random_rcpsp::get_random_rcpsp(14, 30, 10, 5, 20);
+         let other = psplib::parse_sm(&instance::to_sm(&project)).unwrap();
+         assert_eq!(project.resources.len(), other.resources.len());
+         for (a, b) in project.activities.iter().zip(other.activities.iter()) {
+             assert_eq!(a.id, b.id);
+             assert_eq!(a.duration, b.duration);
+             assert_eq!(a.successors, b.successors);
+             assert_eq!(a.get_demand(), b.get_demand());
+         }
+     }
+ }
@@ -0,0 +1,129 @@
+use crate::prcpsp::project::Project as Project;
+use crate::prcpsp::activity::Activity as Activity;
+
+/**
+* Return a planned project with each activity shifted as late as possible without
+* changing the makespan, in decreasing order of finish times. An activity finishes
+* before its successors start and only moves to times where resources are available,
+* free workers have the skills it needs and lags, deadlines and segments aren't missed
+* more than before. The dummy start and end activities don't move.
+* project: planned project.
+* makespan: finish time of project.
+*/
+pub fn right_justify(project: &Project, makespan: i32) -> Project {
+    let mut project = project.clone();
+    let n = project.activities.len();
+    if n < 3 {
+        return project;
+    }
+    let mut order : Vec<(u32, i32)> = project.activities[1..n - 1].iter()
+                                                                  .map(|x| (x.id, x.start_time + x.duration as i32))
+                                                                  .collect();
+    order.sort_by_key(|(_, finish)| -finish);
+    for (id, _) in order {
+        let index = project.activities.iter().position(|x| x.id == id).unwrap();
+        let activity = project.activities[index].clone();
+        let latest = activity.successors.iter()
+                                        .filter_map(|s| project.activities.iter().find(|x| x.id == *s))
+                                        .map(|x| x.start_time)
+                                        .filter(|x| *x >= 0)
+                                        .min()
+                                        .unwrap_or(makespan);
+        let excess = violations(&project, &activity);
+        let mut moved = false;
+        project.activities[index].start_time = -1;
+        for time in ((activity.start_time + 1)..=(latest - activity.duration as i32)).rev() {
+            if project.plan_activity_at(&activity, time) {
+                let planned = &project.activities[index];
+                if planned.start_time + planned.duration as i32 <= latest && violations(&project, &activity) <= excess {
+                    moved = true;
+                    break;
+                }
+                project.activities[index] = activity.clone();
+                project.activities[index].start_time = -1;
+            }
+        }
+        if !moved {
+            project.activities[index] = activity;
+        }
+    }
+    project
+}
+
+/**
+* Return an activity list ordered by the start times of a planned project, keeping
+* the order of the given list for activities that start at the same time.
+* project: planned project.
+* planning: activity list of the project.
+*/
+pub fn start_order(project: &Project, planning: &[u32]) -> Vec<u32> {
+    let mut order = planning.to_vec();
+    order.sort_by_key(|id| project.activities.iter().find(|x| x.id == *id).map_or(0, |x| x.start_time));
+    order
+}
+
+/**
+* Return the time units that an activity of a planned project misses the lags of its
+* relations, its deadline and the segments of its original activity. Only these can
+* change when the activity moves.
+* project: planned project.
+* activity: activity that moves.
+*/
+fn violations(project: &Project, activity: &Activity) -> u32 {
+    let start = |id: u32| project.activities.iter().find(|x| x.id == id).filter(|x| x.start_time != -1);
+    let lags : u32 = project.relations.iter()
+                                      .filter(|r| r.from == activity.id || r.to == activity.id)
+                                      .filter_map(|r| match (start(r.from), start(r.to)) {
+                                          (Some(from), Some(to)) => Some(r.excess(from.start_time, from.duration, to.start_time, to.duration)),
+                                          _ => None,
+                                      })
+                                      .sum();
+    let lateness = start(activity.id).map_or(0, |x| x.get_lateness());
+    lags + lateness + project.activity_segment_excess(activity.get_parent())
+}
+
+#[cfg(test)]
+ mod tests {
+     use crate::prcpsp::justification as justification;
+     use crate::prcpsp::csv_project as csv_project;
+     use crate::prcpsp::psplib as psplib;
+     use crate::prcpsp::decoder::Decoder as Decoder;
+     use crate::prcpsp::sa_state::SaState as SaState;
+     use crate::prcpsp::validator as validator;
+     use crate::metaheuristics::state::State as State;
+
+     #[test]
+     fn test_justification() {
+         let resources = "name,capacity\ncrew,2\n";
+         let activities = "id,name,duration,predecessors,crew\nA,Pour,3,,1\nB,Cure,1,A,1\nC,Paint,3,,1\n";
+         let project = csv_project::parse_csv(activities, resources).unwrap();
+         let mut state = SaState::from_planning(project.pre_emptive_project(0), vec![1,2,3,4,5], 14).unwrap();
+         assert_eq!(state.times, vec![0,0,3,3,6]);
+
+         // Pour and Cure go right against Paint, then left again in the order of Paint first
+         let right = justification::right_justify(&state.project, 6);
+         assert_eq!(right.activities.iter().map(|x| x.start_time).collect::<Vec<i32>>(), vec![0,2,5,3,6]);
+         assert!(validator::validate(&right, &state.planning, &[0,2,5,3,6]).is_empty());
+         assert_eq!(justification::start_order(&right, &state.planning), vec![1,2,4,3,5]);
+         assert!(state.justify());
+         assert_eq!(state.planning, vec![1,2,4,3,5]);
+         assert_eq!(state.times, vec![0,0,0,3,4]);
+         assert!(!state.justify());
+
+         let project = psplib::read_sm("examples/psplib-9.sm").unwrap().pre_emptive_project(2);
+         for decoder in &[Decoder::Serial, Decoder::Parallel] {
+             let mut state = SaState::new(project.clone(), 14);
+             state.set_decoder(*decoder);
+             let cost = state.get_cost();
+             state.justify();
+             assert!(state.get_cost() <= cost);
+             assert!(validator::validate(&state.project, &state.planning, &state.times).is_empty());
+
+             state.set_justification(true);
+             let (neighbor_cost, movement, _) = State::get_neighbor(&mut state);
+             state.set_neighbor(movement);
+             assert_eq!(state.get_cost(), neighbor_cost);
+             assert!(validator::validate(&state.project, &state.planning, &state.times).is_empty());
+         }
+     }
+ }
@@ -0,0 +1,384 @@
+use std::collections::HashMap;
+use std::fs;
+
+use crate::prcpsp::project::Project as Project;
+use crate::prcpsp::sa_state::SaState as SaState;
+
+/**
+* Sense of a linear constraint.
+*/
+#[derive(Debug,Clone,Copy,PartialEq)]
+pub enum Sense {
+    Equal,
+    Greater,
+    Less,
+}
+
+/**
+* Linear constraint: terms (coefficient, variable) sense rhs.
+*/
+#[derive(Debug,Clone)]
+pub struct Constraint {
+    pub name: String,
+    pub terms: Vec<(i64, String)>,
+    pub sense: Sense,
+    pub rhs: i64,
+}
+
+/**
+* Minimization model with binary variables.
+*/
+#[derive(Debug,Clone)]
+pub struct Model {
+    pub objective: Vec<(i64, String)>,
+    pub constraints: Vec<Constraint>,
+    pub binaries: Vec<String>,
+}
+
+/**
+* Return the name of the variable x_j_t, which is 1 if activity j starts at time t.
+*/
+pub fn variable(id: u32, time: i32) -> String {
+    format!("x_{}_{}", id, time)
+}
+
+/**
+* Return the earliest and latest start time of each activity according
+* to precedences, given a horizon for the project. The initial activity starts at 0.
+* Release times and deadlines of activities narrow their windows, which are
+* empty (the latest start is before the earliest) if a deadline can't be met.
+* project: project to check.
+* horizon: maximum finish time of project.
+*/
+pub fn time_windows(project: &Project, horizon: i32) -> Vec<(i32, i32)> {
+    let n = project.activities.len();
+    let index = |id: u32| project.activities.iter().position(|x| x.id == id).unwrap();
+    let mut earliest : Vec<i32> = project.activities.iter().map(|x| x.release).collect();
+    let mut tail : Vec<i32> = vec![0; n];
+    for _ in 0..n {
+        let mut changed = false;
+        for (i, a) in project.activities.iter().enumerate() {
+            for s in &a.successors {
+                let j = index(*s);
+                if earliest[i] + a.duration as i32 > earliest[j] {
+                    earliest[j] = earliest[i] + a.duration as i32;
+                    changed = true;
+                }
+                let length = a.duration as i32 + tail[j];
+                if length > tail[i] {
+                    tail[i] = length;
+                    changed = true;
+                }
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+    let mut latest : Vec<i32> = project.activities.iter()
+                                                  .enumerate()
+                                                  .map(|(i, a)| {
+                                                      let latest = horizon - tail[i].max(a.duration as i32);
+                                                      if a.deadline >= 0 { latest.min(a.deadline - a.duration as i32) } else { latest }
+                                                  })
+                                                  .collect();
+    // Deadlines also limit the start of predecessors
+    for _ in 0..n {
+        let mut changed = false;
+        for (i, a) in project.activities.iter().enumerate() {
+            for s in &a.successors {
+                let j = index(*s);
+                if latest[j] - (a.duration as i32) < latest[i] {
+                    latest[i] = latest[j] - a.duration as i32;
+                    changed = true;
+                }
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+    let mut windows : Vec<(i32, i32)> = earliest.into_iter().zip(latest).collect();
+    // Initial activity starts the project
+    windows[0] = (0, 0);
+    windows
+}
+
+/**
+* Create the time-indexed (pulse) model of a project, minimizing the start time of
+* the last activity. Subactivities of a pre-emptive project are activities of the model.
+* Return an error if an activity can't start within the horizon or its deadline.
+* project: project to model.
+* horizon: maximum finish time, as the makespan of a known solution.
+*/
+pub fn get_model(project: &Project, horizon: i32) -> Result<Model, String> {
+    let windows = time_windows(project, horizon);
+    if let Some((a, (es, _))) = project.activities.iter().zip(windows.iter()).find(|(_, (es, ls))| es > ls) {
+        return Err(format!("La actividad {} no puede empezar en {} y terminar dentro del horizonte {} o su fecha limite", a.id, es, horizon));
+    }
+    let mut constraints : Vec<Constraint> = vec![];
+    let mut binaries : Vec<String> = vec![];
+
+    for (a, (es, ls)) in project.activities.iter().zip(windows.iter()) {
+        let terms : Vec<(i64, String)> = (*es..=*ls).map(|t| (1, variable(a.id, t))).collect();
+        binaries.extend(terms.iter().map(|(_, x)| x.clone()));
+        constraints.push(Constraint { name: format!("a_{}", a.id), terms, sense: Sense::Equal, rhs: 1 });
+    }
+
+    for (j, a) in project.activities.iter().enumerate() {
+        for pred in &a.predecessors {
+            let i = project.activities.iter().position(|x| x.id == *pred).unwrap();
+            let predecessor = &project.activities[i];
+            let mut terms : Vec<(i64, String)> = (windows[j].0..=windows[j].1).map(|t| (t as i64, variable(a.id, t))).collect();
+            terms.extend((windows[i].0..=windows[i].1).map(|t| (-(t as i64), variable(predecessor.id, t))));
+            constraints.push(Constraint { name: format!("p_{}_{}", predecessor.id, a.id), terms, sense: Sense::Greater, rhs: predecessor.duration as i64 });
+        }
+    }
+
+    for relation in &project.relations {
+        let i = project.activities.iter().position(|x| x.id == relation.from).unwrap();
+        let j = project.activities.iter().position(|x| x.id == relation.to).unwrap();
+        let from_offset = if relation.kind.is_from_start() { 0 } else { project.activities[i].duration as i64 };
+        let to_offset = if relation.kind.is_to_start() { 0 } else { project.activities[j].duration as i64 };
+        let mut terms : Vec<(i64, String)> = (windows[j].0..=windows[j].1).map(|t| (t as i64, variable(relation.to, t))).collect();
+        terms.extend((windows[i].0..=windows[i].1).map(|t| (-(t as i64), variable(relation.from, t))));
+        constraints.push(Constraint { name: format!("l_{}_{}", relation.from, relation.to), terms: terms.clone(), sense: Sense::Greater,
+                                      rhs: relation.min_lag as i64 + from_offset - to_offset });
+        if let Some(max_lag) = relation.max_lag {
+            constraints.push(Constraint { name: format!("u_{}_{}", relation.from, relation.to), terms, sense: Sense::Less,
+                                          rhs: max_lag as i64 + from_offset - to_offset });
+        }
+    }
+
+    for resource in &project.resources {
+        for t in 0..horizon {
+            let mut terms : Vec<(i64, String)> = vec![];
+            for (a, (es, ls)) in project.activities.iter().zip(windows.iter()) {
+                let usage = a.supplies.iter().find(|x| x.resource == *resource).map_or(0, |x| x.usage);
+                if usage == 0 || a.duration == 0 {
+                    continue;
+                }
+                let first = (*es).max(t - a.duration as i32 + 1);
+                let last = (*ls).min(t);
+                terms.extend((first..=last).map(|tau| (usage as i64, variable(a.id, tau))));
+            }
+            let capacity = resource.capacity_at(t) as i64;
+            if terms.iter().map(|(c, _)| c).sum::<i64>() > capacity {
+                constraints.push(Constraint { name: format!("r_{}_{}", resource.id, t), terms, sense: Sense::Less, rhs: capacity });
+            }
+        }
+    }
+
+    let last = project.activities.last().unwrap();
+    let (es, ls) = windows[windows.len() - 1];
+    let objective = (es..=ls).map(|t| (t as i64, variable(last.id, t))).collect();
+    Ok(Model { objective, constraints, binaries })
+}
+
+/**
+* Return the terms of a linear expression in LP format, in lines of a few terms.
+*/
+fn lp_terms(terms: &[(i64, String)]) -> String {
+    let mut content = String::new();
+    for (i, (coefficient, var)) in terms.iter().enumerate() {
+        if i > 0 && i % 8 == 0 {
+            content.push_str("\n   ");
+        }
+        if *coefficient < 0 {
+            content.push_str(&format!(" - {} {}", -coefficient, var));
+        } else if i == 0 {
+            content.push_str(&format!(" {} {}", coefficient, var));
+        } else {
+            content.push_str(&format!(" + {} {}", coefficient, var));
+        }
+    }
+    content
+}
+
+/**
+* Return the model in CPLEX LP format.
+*/
+pub fn to_lp(model: &Model) -> String {
+    let mut content = String::from("\\ Modelo indexado en el tiempo para PRCPSP\n");
+    content.push_str("Minimize\n obj:");
+    content.push_str(&lp_terms(&model.objective));
+    content.push_str("\nSubject To\n");
+    for c in &model.constraints {
+        let sense = match c.sense {
+            Sense::Equal => "=",
+            Sense::Greater => ">=",
+            Sense::Less => "<=",
+        };
+        content.push_str(&format!(" {}:{}\n    {} {}\n", c.name, lp_terms(&c.terms), sense, c.rhs));
+    }
+    content.push_str("Binary\n");
+    for var in &model.binaries {
+        content.push_str(&format!(" {}\n", var));
+    }
+    content.push_str("End\n");
+    content
+}
+
+/**
+* Return the model in (free) MPS format.
+*/
+pub fn to_mps(model: &Model) -> String {
+    let mut content = String::from("NAME prcpsp\nROWS\n N obj\n");
+    for c in &model.constraints {
+        let sense = match c.sense {
+            Sense::Equal => "E",
+            Sense::Greater => "G",
+            Sense::Less => "L",
+        };
+        content.push_str(&format!(" {} {}\n", sense, c.name));
+    }
+    // Coefficients of each variable by row, in the order of the rows
+    let mut columns : HashMap<&str, Vec<(&str, i64)>> = HashMap::new();
+    let rows = std::iter::once(("obj", &model.objective)).chain(model.constraints.iter().map(|c| (c.name.as_str(), &c.terms)));
+    for (row, terms) in rows {
+        for (coefficient, var) in terms {
+            let column = columns.entry(var.as_str()).or_default();
+            match column.last_mut() {
+                Some((last, value)) if *last == row => *value += coefficient,
+                _ => column.push((row, *coefficient)),
+            }
+        }
+    }
+    content.push_str("COLUMNS\n");
+    content.push_str(" MARKER 'MARKER' 'INTORG'\n");
+    for var in &model.binaries {
+        for (row, coefficient) in columns.get(var.as_str()).into_iter().flatten().filter(|(_, x)| *x != 0) {
+            content.push_str(&format!(" {} {} {}\n", var, row, coefficient));
+        }
+    }
+    content.push_str(" MARKER 'MARKER' 'INTEND'\n");
+    content.push_str("RHS\n");
+    for c in model.constraints.iter().filter(|x| x.rhs != 0) {
+        content.push_str(&format!(" RHS {} {}\n", c.name, c.rhs));
+    }
+    content.push_str("BOUNDS\n");
+    for var in &model.binaries {
+        content.push_str(&format!(" BV BND {}\n", var));
+    }
+    content.push_str("ENDATA\n");
+    content
+}
+
+/**
+* Read the start times from a solution file of a MIP solver.
+* Lines with a variable x_j_t followed by its value (Gurobi, CBC, SCIP, ...)
+* and CPLEX XML solutions are accepted. Return pairs (activity, start time).
+* contents: text of the solution.
+*/
+pub fn parse_solution(contents: &str) -> Vec<(u32, i32)> {
+    let mut starts : Vec<(u32, i32)> = vec![];
+    for line in contents.lines() {
+        let (name, value) = if line.contains("name=\"") {
+            (attribute(line, "name"), attribute(line, "value").and_then(|x| x.parse::<f64>().ok()))
+        } else {
+            let tokens : Vec<&str> = line.split_whitespace().collect();
+            match tokens.iter().position(|x| x.starts_with("x_")) {
+                Some(i) => (Some(tokens[i].to_string()), tokens.get(i + 1).and_then(|x| x.parse::<f64>().ok())),
+                None => (None, None),
+            }
+        };
+        if let (Some(name), Some(value)) = (name, value) {
+            let parts : Vec<&str> = name.split('_').collect();
+            if parts.len() != 3 || value < 0.5 {
+                continue;
+            }
+            if let (Ok(id), Ok(time)) = (parts[1].parse::<u32>(), parts[2].parse::<i32>()) {
+                starts.push((id, time));
+            }
+        }
+    }
+    starts
+}
+
+/**
+* Return the value of an XML attribute in a line.
+*/
+fn attribute(line: &str, name: &str) -> Option<String> {
+    let key = format!(" {}=\"", name);
+    let start = line.find(&key)? + key.len();
+    let end = line[start..].find('"')? + start;
+    Some(line[start..end].to_string())
+}
+
+/**
+* Create a state with the start times of a MIP solver solution.
+* project: project of the model.
+* filename: path to the solution of the solver.
+* seed: seed for the state.
+*/
+pub fn read_solution(mut project: Project, filename: &str, seed: u64) -> Result<SaState, String> {
+    let contents = fs::read_to_string(filename)
+            .map_err(|e| format!("No se pudo leer el archivo {}: {}", filename, e))?;
+    let starts = parse_solution(&contents);
+    for activity in &mut project.activities {
+        let times : Vec<i32> = starts.iter().filter(|(id, _)| *id == activity.id).map(|(_, t)| *t).collect();
+        if times.len() != 1 {
+            return Err(format!("La actividad {} tiene {} tiempos de inicio en la solucion", activity.id, times.len()));
+        }
+        activity.start_time = times[0];
+    }
+    let first = project.activities.first().unwrap().start_time;
+    let last = project.activities.last().unwrap().start_time;
+    if project.activities.iter().any(|x| x.start_time < first || x.start_time > last) {
+        return Err(String::from("Actividades ficticias de inicio y fin no encontradas"));
+    }
+    Ok(SaState::from_project_planned(project, seed))
+}
+
+#[cfg(test)]
+ mod tests {
+     use crate::prcpsp::milp as milp;
+     use crate::prcpsp::psplib as psplib;
+     use crate::prcpsp::validator as validator;
+     use crate::prcpsp::relation as relation;
+
+     #[test]
+     fn test_model() {
+         let project = psplib::read_sm("examples/psplib-9.sm").unwrap();
+         let windows = milp::time_windows(&project, 12);
+         assert_eq!(windows[0], (0, 0));
+         assert_eq!(windows[6], (2, 7));
+         assert_eq!(windows[8], (7, 12));
+
+         let model = milp::get_model(&project, 12).unwrap();
+         assert_eq!(model.constraints.iter().filter(|x| x.name.starts_with("a_")).count(), 9);
+         assert_eq!(model.constraints.iter().filter(|x| x.name.starts_with("p_")).count(), 11);
+         let lp = milp::to_lp(&model);
+         assert!(lp.contains(" a_2: 1 x_2_0 + 1 x_2_1"));
+         assert!(lp.contains(" p_2_6: 1 x_6_1 + 2 x_6_2"));
+         let mps = milp::to_mps(&model);
+         assert!(mps.contains(" G p_2_6\n"));
+         assert!(mps.contains(" x_9_10 obj 10\n"));
+
+         let mut project = project;
+         relation::parse_relations(&mut project, "2 3 FF 0 3\n").unwrap();
+         let model = milp::get_model(&project, 12).unwrap();
+         let lower = model.constraints.iter().find(|x| x.name == "l_2_3").unwrap();
+         let upper = model.constraints.iter().find(|x| x.name == "u_2_3").unwrap();
+         assert_eq!((lower.rhs, upper.rhs), (-1, 2));
+         assert!(milp::get_model(&project, 6).is_err());
+     }
+
+     #[test]
+     fn test_read_solution() {
+         let project = psplib::read_sm("examples/psplib-9.sm").unwrap();
+         let gurobi = "# Objective value = 10\nx_1_0 1\nx_2_0 1\nx_3_0 1\nx_3_1 0\nx_4_0 1\nx_5_2 1\nx_6_4 1\nx_7_5 1\nx_8_5 1\nx_9_10 1\n";
+         let cplex = "<CPLEXSolution>\n<variables>\n<variable name=\"x_1_0\" index=\"0\" value=\"1\"/>\n<variable name=\"x_2_0\" index=\"1\" value=\"1\"/>\n\
+                      <variable name=\"x_3_0\" index=\"2\" value=\"1\"/>\n<variable name=\"x_4_0\" index=\"3\" value=\"1\"/>\n<variable name=\"x_5_2\" index=\"4\" value=\"1\"/>\n\
+                      <variable name=\"x_6_4\" index=\"5\" value=\"1\"/>\n<variable name=\"x_7_5\" index=\"6\" value=\"1\"/>\n<variable name=\"x_8_5\" index=\"7\" value=\"1\"/>\n\
+                      <variable name=\"x_9_10\" index=\"8\" value=\"1\"/>\n<variable name=\"x_9_11\" index=\"9\" value=\"0\"/>\n</variables>\n</CPLEXSolution>\n";
+         assert_eq!(milp::parse_solution(gurobi), milp::parse_solution(cplex));
+
+         let path = std::env::temp_dir().join("prcpsp-milp-test.sol");
+         std::fs::write(&path, gurobi).unwrap();
+         let state = milp::read_solution(project.clone(), path.to_str().unwrap(), 0).unwrap();
+         assert_eq!(state.get_makespan(), 10);
+         assert!(validator::validate(&state.project, &state.planning, &state.times).is_empty());
+         std::fs::remove_file(&path).unwrap();
+     }
+ }
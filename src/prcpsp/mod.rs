@@ -2,6 +2,29 @@ pub mod activity;
 pub mod project;
 pub mod rn_resource;
 pub mod rn_resource_usage;
+pub mod nr_resource;
+pub mod nr_resource_usage;
+pub mod mode;
 pub mod sa_state;
 pub mod random_rcpsp;
 pub mod utils;
+pub mod psplib;
+pub mod patterson;
+pub mod instance;
+pub mod solution;
+pub mod validator;
+pub mod bounds;
+pub mod milp;
+pub mod csv_project;
+pub mod schedule_export;
+pub mod calendar;
+pub mod relation;
+pub mod window;
+pub mod preemption;
+pub mod portfolio;
+pub mod objective;
+pub mod worker;
+pub mod stochastic;
+pub mod availability;
+pub mod decoder;
+pub mod justification;
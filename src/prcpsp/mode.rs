@@ -0,0 +1,21 @@
+use crate::prcpsp::rn_resource_usage::RnResourceUsage as RnResourceUsage;
+use crate::prcpsp::nr_resource_usage::NrResourceUsage as NrResourceUsage;
+
+/**
+*   Represents an execution mode of an activity,
+*   with its own duration, resource usage and consumption
+*   of non-renewable resources.
+*/
+#[derive(Debug,Clone)]
+pub struct Mode {
+    pub id: u32,
+    pub duration: u32,
+    pub supplies: Vec<RnResourceUsage>,
+    pub consumptions: Vec<NrResourceUsage>,
+}
+
+impl Mode {
+    pub fn new(id: u32, duration: u32, supplies: Vec<RnResourceUsage>, consumptions: Vec<NrResourceUsage>) -> Mode {
+        Mode { id, duration, supplies, consumptions }
+    }
+}
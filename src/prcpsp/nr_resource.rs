@@ -0,0 +1,36 @@
+use core::cmp::Ordering;
+
+/* Represents a non-renewable resource, as a budget or a stock of material,
+   where capacity is the total units for the whole project. */
+#[derive(Debug,Clone)]
+pub struct NrResource {
+    pub id: u32,
+    pub name: String,
+    pub capacity: u32,
+}
+
+impl NrResource {
+    pub fn new(id: u32, name: String, capacity: u32) -> NrResource {
+        NrResource { id, name, capacity }
+    }
+}
+
+impl PartialEq for NrResource {
+    fn eq(&self, other : &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl PartialOrd for NrResource {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Eq for NrResource {}
+
+impl Ord for NrResource {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.id.cmp(&other.id)
+    }
+}
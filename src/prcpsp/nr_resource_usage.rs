@@ -0,0 +1,17 @@
+use crate::prcpsp::nr_resource::NrResource as NrResource;
+
+/**
+*   Represents the units that actual activity consumes
+*   of a non-renewable resource.
+*/
+#[derive(Debug,Clone)]
+pub struct NrResourceUsage {
+    pub resource: NrResource,
+    pub usage: u32,
+}
+
+impl NrResourceUsage {
+    pub fn new(resource: NrResource, usage: u32) -> NrResourceUsage {
+        NrResourceUsage { resource, usage }
+    }
+}
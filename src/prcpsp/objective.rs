@@ -0,0 +1,264 @@
+use std::fmt;
+use std::fs;
+
+use crate::prcpsp::project::Project as Project;
+
+/**
+* Function minimized by the metaheuristics for a planned project.
+* Portfolio objectives use the finish time of each project of a portfolio,
+* for a single project they're the same as the makespan.
+*/
+#[derive(Debug,Clone,Copy,PartialEq)]
+pub enum Objective {
+    /* Finish time of the last activity. */
+    Makespan,
+    /* Sum of the finish times of the projects. */
+    TotalMakespan,
+    /* Sum of the delays of the projects after their due dates, times their weights. */
+    WeightedDelay,
+    /* Latest finish time of a project. */
+    MaxMakespan,
+    /* Sum of the tardiness of the activities after their due dates, times their weights. */
+    WeightedTardiness,
+    /* Sum of the finish times of the activities, times their weights. */
+    WeightedCompletion,
+    /* Net present value of the cash flows of the activities with a discount rate per time unit. */
+    NetPresentValue(f64),
+    /* Sum of the squares of the usage of each resource at each time. */
+    ResourceLeveling,
+    /* Sum of the capacity of each resource times its unit cost, with the capacities chosen by the search. */
+    CapacityCost,
+}
+
+impl Objective {
+    /**
+    * Return the objective from its name, as makespan or delay.
+    * The discount rate of the net present value is given as npv:<rate>, 0.01 by default.
+    */
+    pub fn from_str(value: &str) -> Option<Objective> {
+        let value = value.to_lowercase();
+        if let Some(rate) = value.strip_prefix("npv:") {
+            return rate.parse::<f64>().ok().filter(|x| *x >= 0.0).map(Objective::NetPresentValue);
+        }
+        match value.as_str() {
+            "makespan" => Some(Objective::Makespan),
+            "total" => Some(Objective::TotalMakespan),
+            "delay" => Some(Objective::WeightedDelay),
+            "max" => Some(Objective::MaxMakespan),
+            "tardiness" => Some(Objective::WeightedTardiness),
+            "completion" => Some(Objective::WeightedCompletion),
+            "npv" => Some(Objective::NetPresentValue(0.01)),
+            "leveling" => Some(Objective::ResourceLeveling),
+            "cost" => Some(Objective::CapacityCost),
+            _ => None,
+        }
+    }
+
+    /**
+    * Return the value of the objective for a planned project.
+    * The net present value is maximized, so its value is the opposite of the net present value.
+    * project: project with the start time of every activity.
+    * makespan: finish time of project.
+    */
+    pub fn evaluate(&self, project: &Project, makespan: u32) -> f64 {
+        let finishes = project.subprojects.iter().map(|x| (x, project.subproject_finish(x) as f64));
+        match self {
+            Objective::Makespan => makespan as f64,
+            Objective::TotalMakespan | Objective::WeightedDelay | Objective::MaxMakespan if project.subprojects.is_empty() => makespan as f64,
+            Objective::TotalMakespan => finishes.map(|(_, finish)| finish).sum(),
+            Objective::WeightedDelay => finishes.map(|(x, finish)| x.weight as f64 * (finish - x.due_date as f64).max(0.0)).sum(),
+            Objective::MaxMakespan => finishes.map(|(_, finish)| finish).fold(0.0, f64::max),
+            Objective::WeightedTardiness => project.activity_finishes()
+                                                   .iter()
+                                                   .filter(|(x, _)| x.due_date >= 0)
+                                                   .map(|(x, finish)| x.weight as f64 * (finish - x.due_date).max(0) as f64)
+                                                   .sum(),
+            Objective::WeightedCompletion => project.activity_finishes()
+                                                    .iter()
+                                                    .map(|(x, finish)| x.weight as f64 * (*finish).max(0) as f64)
+                                                    .sum(),
+            Objective::NetPresentValue(rate) => -net_present_value(project, *rate),
+            Objective::ResourceLeveling => project.resource_profile(makespan)
+                                                  .iter()
+                                                  .flat_map(|x| x.iter())
+                                                  .map(|x| (x * x) as f64)
+                                                  .sum(),
+            Objective::CapacityCost => project.capacity_cost() as f64,
+        }
+    }
+
+    /**
+    * Return a bound of the difference between two values of the objective for planned
+    * projects finishing by a time, so one unit of penalty more than it outweighs any
+    * difference in the objective.
+    * project: project to bound.
+    * horizon: latest finish time of project.
+    */
+    pub fn upper_bound(&self, project: &Project, horizon: u32) -> u32 {
+        match self {
+            Objective::Makespan | Objective::MaxMakespan => horizon,
+            Objective::TotalMakespan => (project.subprojects.len().max(1) as u32).saturating_mul(horizon),
+            Objective::WeightedDelay => project.subprojects.iter().map(|x| x.weight).sum::<u32>().max(1).saturating_mul(horizon),
+            Objective::WeightedTardiness | Objective::WeightedCompletion => project.activities.iter()
+                                                                                  .map(|x| x.weight)
+                                                                                  .sum::<u32>()
+                                                                                  .saturating_mul(horizon),
+            Objective::NetPresentValue(_) => project.activities.iter().map(|x| x.cash_flow.unsigned_abs()).sum(),
+            Objective::ResourceLeveling => project.resources.iter()
+                                                  .map(|r| r.periods.iter().map(|x| x.2).fold(r.capacity, u32::max).pow(2))
+                                                  .sum::<u32>()
+                                                  .saturating_mul(horizon),
+            Objective::CapacityCost => project.resources.iter().map(|x| x.cost * project.capacity_bounds(x).1).sum(),
+        }
+    }
+}
+
+impl fmt::Display for Objective {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Objective::Makespan => write!(f, "makespan"),
+            Objective::TotalMakespan => write!(f, "total"),
+            Objective::WeightedDelay => write!(f, "delay"),
+            Objective::MaxMakespan => write!(f, "max"),
+            Objective::WeightedTardiness => write!(f, "tardiness"),
+            Objective::WeightedCompletion => write!(f, "completion"),
+            Objective::NetPresentValue(rate) => write!(f, "npv:{}", rate),
+            Objective::ResourceLeveling => write!(f, "leveling"),
+            Objective::CapacityCost => write!(f, "cost"),
+        }
+    }
+}
+
+/**
+* Return the net present value of a planned project, where the cash flow of each
+* activity happens when it finishes and is discounted by (1 + rate)^finish.
+* project: project with the start time of every activity.
+* rate: discount rate per time unit.
+*/
+pub fn net_present_value(project: &Project, rate: f64) -> f64 {
+    project.activity_finishes()
+           .iter()
+           .map(|(x, finish)| x.cash_flow as f64 / (1.0 + rate).powi((*finish).max(0)))
+           .sum()
+}
+
+/*
+* Weights, due dates and cash flows of activities for the objectives.
+*
+* File format (lines starting with '#' are comments):
+*
+*   <activity> <weight> [<due_date> [<cash_flow>]]
+*
+* where activity is the id or name of an activity, the weight is 1 by default, a due date
+* of -1 or without value means the activity doesn't have one and the cash flow, 0 by default,
+* is negative for payments and positive for incomes.
+*/
+
+/**
+* Read a file of weights, due dates and cash flows and set them to the activities of a project.
+* project: project whose activities are changed.
+* filename: path to the costs.
+*/
+pub fn read_costs(project: &mut Project, filename: &str) -> Result<(), String> {
+    let contents = fs::read_to_string(filename)
+            .map_err(|e| format!("No se pudo leer el archivo {}: {}", filename, e))?;
+    parse_costs(project, &contents)
+}
+
+/**
+* Set the weights, due dates and cash flows of a text to the activities of a project.
+* project: project whose activities are changed.
+* contents: text of the costs.
+*/
+pub fn parse_costs(project: &mut Project, contents: &str) -> Result<(), String> {
+    for line in contents.lines().map(|x| x.trim()).filter(|x| !x.is_empty() && !x.starts_with('#')) {
+        let tokens : Vec<&str> = line.split_whitespace().collect();
+        if tokens.len() < 2 || tokens.len() > 4 {
+            return Err(format!("Costos incompletos: '{}'", line));
+        }
+        let number = |i: usize, default: i32| match tokens.get(i) {
+            Some(t) => t.parse::<i32>().map_err(|_| format!("Valor invalido '{}' en '{}'", t, line)),
+            None => Ok(default),
+        };
+        let weight = number(1, 1)?;
+        let due_date = number(2, -1)?;
+        let cash_flow = number(3, 0)?;
+        if weight < 0 || due_date < -1 {
+            return Err(format!("Costos invalidos '{}'", line));
+        }
+        let activity = project.activities.iter_mut()
+                                         .find(|x| x.id.to_string() == tokens[0] || x.name == tokens[0])
+                                         .ok_or_else(|| format!("Actividad {} no encontrada en '{}'", tokens[0], line))?;
+        activity.weight = weight as u32;
+        activity.due_date = due_date;
+        activity.cash_flow = cash_flow;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+ mod tests {
+     use crate::prcpsp::objective as objective;
+     use crate::prcpsp::objective::Objective as Objective;
+     use crate::prcpsp::csv_project as csv_project;
+     use crate::prcpsp::window as window;
+     use crate::prcpsp::sa_state::SaState as SaState;
+     use crate::metaheuristics::state::State as State;
+     use crate::metaheuristics::cost::Cost as Cost;
+
+     #[test]
+     fn test_objectives() {
+         let resources = "name,capacity\ncrew,2\n";
+         let activities = "id,name,duration,predecessors,crew,weight,due_date,cash_flow\nA,Walls,2,,1,3,1,-10\nB,Roof,2,A,2,,,\nC,Paint,1,,1,2,,50\n";
+         let project = csv_project::parse_csv(activities, resources).unwrap();
+         let mut other = csv_project::parse_csv("id,name,duration,predecessors,crew\nA,Walls,2,,1\nB,Roof,2,A,2\nC,Paint,1,,1\n", resources).unwrap();
+         objective::parse_costs(&mut other, "# pagos al terminar\nWalls 3 1 -10\nPaint 2 -1 50\n").unwrap();
+         for (a, b) in project.activities.iter().zip(other.activities.iter()) {
+             assert_eq!((a.weight, a.due_date, a.cash_flow), (b.weight, b.due_date, b.cash_flow));
+         }
+         let mut state = SaState::from_planning(project.pre_emptive_project(0), vec![1,2,4,3,5], 14).unwrap();
+         assert_eq!(state.times, vec![0,0,0,2,4]);
+         let finishes : Vec<i32> = state.project.activity_finishes().iter().map(|x| x.1).collect();
+         assert_eq!(finishes, vec![2,4,1]);
+
+         assert_eq!(state.get_cost(), (0, 4.0));
+         state.set_objective(Objective::WeightedTardiness);
+         assert_eq!(state.get_cost(), (0, 3.0));
+         state.set_objective(Objective::WeightedCompletion);
+         assert_eq!(state.get_cost(), (0, (3 * 2 + 4 + 2) as f64));
+         state.set_objective(Objective::ResourceLeveling);
+         assert_eq!(state.project.resource_profile(4), vec![vec![2,1,2,2]]);
+         assert_eq!(state.get_cost(), (0, 13.0));
+         state.set_objective(Objective::from_str("npv:0.1").unwrap());
+         let npv = objective::net_present_value(&state.project, 0.1);
+         assert!((npv - (-10.0 / 1.21 + 50.0 / 1.1)).abs() < 1e-9);
+         assert_eq!(state.get_cost(), (0, -npv));
+
+         assert_eq!(Objective::from_str("npv"), Some(Objective::NetPresentValue(0.01)));
+         assert_eq!(Objective::from_str("npv:-1"), None);
+         let mut project = project;
+         assert!(objective::parse_costs(&mut project, "Walls\n").is_err());
+         assert!(objective::parse_costs(&mut project, "Walls -1\n").is_err());
+         assert!(objective::parse_costs(&mut project, "Floor 1\n").is_err());
+     }
+
+     #[test]
+     fn test_leveling_deadline() {
+         let resources = "name,capacity\ncrew,10\n";
+         let activities = "id,name,duration,predecessors,crew\nA,Walls,2,,5\nB,Roof,2,,5\n";
+         let mut project = csv_project::parse_csv(activities, resources).unwrap();
+         window::parse_windows(&mut project, "4 0 2\n").unwrap();
+         let mut state = SaState::from_planning(project.pre_emptive_project(0), vec![1,2,3,4], 14).unwrap();
+         state.set_objective(Objective::ResourceLeveling);
+         assert_eq!(state.get_cost(), (0, 200.0));
+
+         // Roof after Walls levels the crew but misses the deadline, so it's worse
+         let mut late = state.clone();
+         late.project.activities[2].start_time = 2;
+         late.project.activities[3].start_time = 4;
+         late.times = vec![0,0,2,4];
+         assert_eq!(late.get_penalty(), 2 * (4 * 10 * 10 + 1));
+         assert!(state.get_cost() < late.get_cost());
+         assert!(late.get_cost().difference(&state.get_cost()) > 4.0 * 10.0 * 10.0);
+     }
+ }
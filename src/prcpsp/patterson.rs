@@ -0,0 +1,137 @@
+use std::fs;
+
+use crate::prcpsp::project::Project as Project;
+use crate::prcpsp::rn_resource::RnResource as RnResource;
+use crate::prcpsp::psplib as psplib;
+
+/**
+* Read an instance in Patterson format (.rcp files of RG30, RG300 and Patterson sets).
+* filename: path to the instance.
+*/
+pub fn read_rcp(filename: &str) -> Result<Project, String> {
+    let contents = fs::read_to_string(filename)
+            .map_err(|e| format!("No se pudo leer el archivo {}: {}", filename, e))?;
+    parse_rcp(&contents)
+}
+
+/**
+* Create a project from the contents of a Patterson file.
+* The first line has the number of activities (including dummies) and resources,
+* the second one the capacity of each resource and then each activity has
+* its duration, demand for each resource, number of successors and successors.
+* Successors list can continue in the next lines.
+* contents: text of the instance.
+*/
+pub fn parse_rcp(contents: &str) -> Result<Project, String> {
+    let mut lines = contents.lines()
+                            .map(|x| x.trim())
+                            .filter(|x| !x.is_empty())
+                            .enumerate()
+                            .map(|(i, x)| (i + 1, x));
+
+    let (_, header) = lines.next().ok_or_else(|| String::from("Archivo vacio"))?;
+    let header = psplib::numbers(header)?;
+    if header.len() != 2 {
+        return Err(String::from("La primera linea debe tener el numero de actividades y de recursos"));
+    }
+    let jobs = header[0] as usize;
+    let renewable = header[1] as usize;
+
+    let capacities = match lines.next() {
+        Some((_, line)) => psplib::numbers(line)?,
+        None => vec![],
+    };
+    if capacities.len() != renewable {
+        return Err(format!("Se esperaban {} capacidades de recursos, se encontraron {}", renewable, capacities.len()));
+    }
+
+    let mut successors : Vec<(u32, Vec<u32>)> = vec![];
+    let mut requests : Vec<(u32, u32, Vec<u32>)> = vec![];
+    let mut pending = 0;
+    for (n, line) in lines {
+        let values = psplib::numbers(line)?;
+        if pending > 0 {
+            if values.len() > pending {
+                return Err(format!("Linea {}: la actividad {} tiene mas sucesores de los declarados", n, successors.len()));
+            }
+            pending -= values.len();
+            successors.last_mut().unwrap().1.extend(values);
+            continue;
+        }
+        let id = successors.len() as u32 + 1;
+        if id as usize > jobs {
+            return Err(format!("Linea {}: se esperaban {} actividades", n, jobs));
+        }
+        if values.len() < renewable + 2 {
+            return Err(format!("Linea {}: la actividad {} debe tener duracion, {} demandas y numero de sucesores", n, id, renewable));
+        }
+        let count = values[renewable + 1] as usize;
+        let succs = values[renewable + 2..].to_vec();
+        if succs.len() > count {
+            return Err(format!("Linea {}: la actividad {} declara {} sucesores pero tiene {}", n, id, count, succs.len()));
+        }
+        pending = count - succs.len();
+        requests.push((id, values[0], values[1..=renewable].to_vec()));
+        successors.push((id, succs));
+    }
+
+    if pending > 0 {
+        return Err(format!("La actividad {} declara mas sucesores de los que tiene", successors.len()));
+    }
+    if jobs == 0 || successors.len() != jobs {
+        return Err(format!("Se esperaban {} actividades, se encontraron {}", jobs, successors.len()));
+    }
+
+    let resources : Vec<RnResource> = (0..renewable).map(|k| RnResource::new(k as u32 + 1, (k + 1).to_string(), capacities[k])).collect();
+    let activities = psplib::build_activities(&successors, &requests, &resources)?;
+    Ok(Project::new(activities, resources))
+}
+
+#[cfg(test)]
+ mod tests {
+     use crate::prcpsp::patterson as patterson;
+     use crate::prcpsp::sa_state::SaState as SaState;
+
+     const INSTANCE : &str = "9 1
+5
+
+0 0 4 2 3 4 5
+1 1 1 6
+2 2 1 9
+4 2 1 9
+3 2 1 8
+1 2 1 7
+5 1 1 9
+3 2 1 9
+0 0 0
+";
+
+     #[test]
+     fn test_parse_rcp() {
+         let project = patterson::parse_rcp(INSTANCE).unwrap();
+         assert_eq!(project.activities.len(), 9);
+         assert_eq!(project.resources[0].capacity, 5);
+         assert_eq!(project.activities.last().unwrap().predecessors, vec![3,4,7,8]);
+
+         let wrapped = INSTANCE.replace("0 0 4 2 3 4 5", "0 0 4 2 3\n 4 5");
+         let project = patterson::parse_rcp(&wrapped).unwrap();
+         assert_eq!(project.activities[0].successors, vec![2,3,4,5]);
+
+         let state = SaState::new(project, 11);
+         assert_eq!(state.planning, vec![1,2,3,4,5,6,8,7,9]);
+         assert_eq!(state.times, vec![0,0,0,0,2,4,5,5,10]);
+     }
+
+     #[test]
+     fn test_parse_rcp_errors() {
+         let missing_capacities = INSTANCE.replacen("5\n", "", 1);
+         assert!(patterson::parse_rcp(&missing_capacities).is_err());
+         let missing_demand = INSTANCE.replace("4 2 1 9", "4 1");
+         assert!(patterson::parse_rcp(&missing_demand).is_err());
+         let wrong_successors = INSTANCE.replace("1 1 1 6", "1 1 1 6 7");
+         assert!(patterson::parse_rcp(&wrong_successors).is_err());
+         let missing_successors = INSTANCE.replace("0 0 4 2 3 4 5", "0 0 5 2 3 4 5");
+         assert!(patterson::parse_rcp(&missing_successors).is_err());
+         assert!(patterson::parse_rcp("0 1\n5\n").is_err());
+     }
+ }
@@ -0,0 +1,236 @@
+use std::fs;
+use std::path::Path;
+
+use crate::prcpsp::project::Project as Project;
+use crate::prcpsp::activity::Activity as Activity;
+use crate::prcpsp::rn_resource::RnResource as RnResource;
+use crate::prcpsp::rn_resource_usage::RnResourceUsage as RnResourceUsage;
+use crate::prcpsp::nr_resource::NrResource as NrResource;
+use crate::prcpsp::relation::Relation as Relation;
+use crate::prcpsp::milp as milp;
+use crate::prcpsp::instance as instance;
+
+/**
+* Project of a portfolio, where end is the id of its end activity
+* in the project that joins every project of the portfolio.
+*/
+#[derive(Debug,Clone,PartialEq)]
+pub struct Subproject {
+    pub name: String,
+    pub weight: u32,
+    pub due_date: i32,
+    pub end: u32,
+}
+
+/**
+* Project of a portfolio before joining it with the others.
+*/
+#[derive(Debug,Clone)]
+pub struct PortfolioProject {
+    pub name: String,
+    pub project: Project,
+    pub weight: u32,
+    pub due_date: i32,
+    pub release: i32,
+}
+
+/*
+* Portfolio of projects sharing a pool of renewable resources (.pf files).
+*
+* Lines starting with '#' are comments and empty lines are ignored.
+*
+*   resources <k>
+*   <id> <capacity> <name>                        (k lines)
+*   projects <p>
+*   <file> [<weight> [<due_date> [<release>]]]    (p lines)
+*
+* Files are relative to the portfolio and name the projects, with the position of the
+* project added if a file is repeated. Resources of each project are the resources
+* of the pool with the same name, whose capacity is shared by every project.
+* The weight is 1 by default, the due date the critical path of the project if it's
+* missing or -1, and the release the time the project can start, 0 by default.
+*/
+
+/**
+* Read a portfolio file, reading each project with the given function.
+* filename: path to the portfolio.
+* read: function that reads a project from its path.
+*/
+pub fn read_portfolio<F: Fn(&str) -> Result<Project, String>>(filename: &str, read: F) -> Result<Project, String> {
+    let contents = fs::read_to_string(filename)
+            .map_err(|e| format!("No se pudo leer el archivo {}: {}", filename, e))?;
+    let directory = Path::new(filename).parent().unwrap_or_else(|| Path::new(""));
+    parse_portfolio(&contents, |file| read(&directory.join(file).to_string_lossy()))
+}
+
+/**
+* Create the project that joins every project of a portfolio from its contents.
+* contents: text of the portfolio.
+* read: function that reads a project from its path.
+*/
+pub fn parse_portfolio<F: Fn(&str) -> Result<Project, String>>(contents: &str, read: F) -> Result<Project, String> {
+    let mut lines = contents.lines()
+                            .map(|x| x.trim())
+                            .filter(|x| !x.is_empty() && !x.starts_with('#'));
+    let k = instance::section_size(lines.next(), "resources")?;
+    let mut resources : Vec<RnResource> = vec![];
+    for _ in 0..k {
+        let line = lines.next().ok_or_else(|| String::from("Faltan recursos"))?;
+        let mut tokens = line.split_whitespace();
+        let id = instance::number::<u32>(tokens.next(), line)?;
+        let capacity = instance::number::<u32>(tokens.next(), line)?;
+        let name = tokens.collect::<Vec<&str>>().join(" ");
+        let name = if name.is_empty() { id.to_string() } else { name };
+        resources.push(RnResource::new(id, name, capacity));
+    }
+
+    let p = instance::section_size(lines.next(), "projects")?;
+    let mut projects : Vec<PortfolioProject> = vec![];
+    for _ in 0..p {
+        let line = lines.next().ok_or_else(|| String::from("Faltan proyectos"))?;
+        let tokens : Vec<&str> = line.split_whitespace().collect();
+        if tokens.len() > 4 {
+            return Err(format!("Proyecto invalido '{}'", line));
+        }
+        let project = read(tokens[0])?;
+        let weight = match tokens.get(1) { Some(t) => instance::number::<u32>(Some(t), line)?, None => 1 };
+        let due_date = match tokens.get(2) { Some(t) => instance::number::<i32>(Some(t), line)?, None => -1 };
+        let release = match tokens.get(3) { Some(t) => instance::number::<i32>(Some(t), line)?, None => 0 };
+        let mut name = Path::new(tokens[0]).file_stem().map_or(tokens[0].to_string(), |x| x.to_string_lossy().to_string());
+        if projects.iter().any(|x| x.name == name) {
+            name = format!("{}-{}", name, projects.len() + 1);
+        }
+        projects.push(PortfolioProject { name, project, weight, due_date, release });
+    }
+    join_projects(projects, resources)
+}
+
+/**
+* Join the projects of a portfolio in one project, with new dummy start and end activities.
+* Activities of each project get consecutive ids and keep its own dummy activities, as
+* milestones for its start and finish. Non-renewable resources aren't shared, they're
+* renamed as <project>:<name>.
+* projects: projects of the portfolio.
+* resources: pool of renewable resources.
+*/
+pub fn join_projects(projects: Vec<PortfolioProject>, resources: Vec<RnResource>) -> Result<Project, String> {
+    let mut start = Activity::new(1, -1, String::from("inicio"), vec![], vec![], vec![], 0, -1);
+    let mut activities : Vec<Activity> = vec![];
+    let mut nr_resources : Vec<NrResource> = vec![];
+    let mut relations : Vec<Relation> = vec![];
+    let mut subprojects : Vec<Subproject> = vec![];
+    let mut offset = 1;
+    for p in projects {
+        let ids : Vec<u32> = p.project.activities.iter().map(|x| x.id).collect();
+        let new_id = |id: u32| offset + ids.iter().position(|x| *x == id).unwrap() as u32 + 1;
+        let pool = |supplies: &[RnResourceUsage]| -> Result<Vec<RnResourceUsage>, String> {
+            supplies.iter()
+                    .map(|s| {
+                        let resource = resources.iter()
+                                                .find(|x| x.name == s.resource.name)
+                                                .ok_or_else(|| format!("El recurso {} del proyecto {} no esta en el portafolio", s.resource.name, p.name))?;
+                        if s.usage > resource.capacity {
+                            return Err(format!("La demanda {} del recurso {} en el proyecto {} es mayor a su capacidad", s.usage, resource.name, p.name));
+                        }
+                        Ok(RnResourceUsage { resource: resource.clone(), ..s.clone() })
+                    })
+                    .collect()
+        };
+        let nr_offset = nr_resources.len() as u32;
+        let own_nr : Vec<NrResource> = p.project.nr_resources.iter()
+                                                              .enumerate()
+                                                              .map(|(i, x)| NrResource::new(nr_offset + i as u32 + 1, format!("{}:{}", p.name, x.name), x.capacity))
+                                                              .collect();
+        for activity in &p.project.activities {
+            let mut a = activity.clone();
+            a.id = new_id(activity.id);
+            a.name = format!("{}:{}", p.name, activity.name);
+            a.predecessors = activity.predecessors.iter().map(|x| new_id(*x)).collect();
+            a.successors = activity.successors.iter().map(|x| new_id(*x)).collect();
+            a.supplies = pool(&activity.supplies)?;
+            a.resume_supplies = pool(&activity.resume_supplies)?;
+            for c in a.consumptions.iter_mut().chain(a.modes.iter_mut().flat_map(|m| m.consumptions.iter_mut())) {
+                let i = p.project.nr_resources.iter().position(|x| *x == c.resource).unwrap();
+                c.resource = own_nr[i].clone();
+            }
+            for (mode, original) in a.modes.iter_mut().zip(activity.modes.iter()) {
+                mode.supplies = pool(&original.supplies)?;
+            }
+            activities.push(a);
+        }
+        for r in &p.project.relations {
+            relations.push(Relation::new(new_id(r.from), new_id(r.to), r.kind, r.min_lag, r.max_lag));
+        }
+        let first = new_id(ids[0]);
+        let last = new_id(*ids.last().unwrap());
+        let due_date = if p.due_date >= 0 { p.due_date } else {
+            p.release + milp::time_windows(&p.project, 0).last().map_or(0, |x| x.0)
+        };
+        let begin = activities.iter_mut().find(|x| x.id == first).unwrap();
+        begin.release = begin.release.max(p.release);
+        begin.add_anteccessor(start.id);
+        start.add_successor(first);
+        subprojects.push(Subproject { name: p.name.clone(), weight: p.weight, due_date, end: last });
+        nr_resources.extend(own_nr);
+        offset += ids.len() as u32;
+    }
+    let mut end = Activity::new(offset + 1, -1, String::from("fin"), vec![], vec![], vec![], 0, -1);
+    for subproject in &subprojects {
+        activities.iter_mut().find(|x| x.id == subproject.end).unwrap().add_successor(end.id);
+        end.add_anteccessor(subproject.end);
+    }
+    activities.insert(0, start);
+    activities.push(end);
+    let mut project = Project::new(activities, resources);
+    project.nr_resources = nr_resources;
+    project.relations = relations;
+    project.subprojects = subprojects;
+    Ok(project)
+}
+
+#[cfg(test)]
+ mod tests {
+     use crate::prcpsp::portfolio as portfolio;
+     use crate::prcpsp::psplib as psplib;
+     use crate::prcpsp::objective::Objective as Objective;
+     use crate::prcpsp::sa_state::SaState as SaState;
+     use crate::prcpsp::validator as validator;
+     use crate::metaheuristics::state::State as State;
+
+     const PORTFOLIO : &str = "# Dos proyectos con la misma cuadrilla
+resources 1
+1 5 1
+projects 2
+examples/psplib-9.sm 2 12
+examples/psplib-9.sm 1 -1 3
+";
+
+     #[test]
+     fn test_portfolio() {
+         let project = portfolio::parse_portfolio(PORTFOLIO, psplib::read_sm).unwrap();
+         assert_eq!(project.activities.len(), 20);
+         assert_eq!(project.activities[0].successors, vec![2, 11]);
+         assert_eq!(project.activities[19].predecessors, vec![10, 19]);
+         assert_eq!(project.activities[12].name, "psplib-9-2:3");
+         assert_eq!(project.subprojects.iter().map(|x| (x.end, x.weight, x.due_date)).collect::<Vec<(u32, u32, i32)>>(), vec![(10, 2, 12), (19, 1, 10)]);
+         assert!(project.activities.iter().flat_map(|x| x.supplies.iter()).all(|x| x.resource == project.resources[0]));
+
+         let mut state = SaState::new(project.pre_emptive_project(1), 14);
+         assert_eq!(state.project.subprojects, project.subprojects);
+         assert!(validator::validate(&state.project, &state.planning, &state.times).is_empty());
+         let finishes : Vec<i32> = state.project.subprojects.iter().map(|x| state.project.subproject_finish(x)).collect();
+         assert!(finishes[1] >= 10);
+         assert_eq!(state.get_cost(), (0, state.get_makespan() as f64));
+         state.set_objective(Objective::MaxMakespan);
+         assert_eq!(state.get_cost(), (0, *finishes.iter().max().unwrap() as f64));
+         state.set_objective(Objective::TotalMakespan);
+         assert_eq!(state.get_cost(), (0, (finishes[0] + finishes[1]) as f64));
+         state.set_objective(Objective::WeightedDelay);
+         assert_eq!(state.get_cost(), (0, (2 * (finishes[0] - 12).max(0) + (finishes[1] - 10).max(0)) as f64));
+
+         assert!(portfolio::parse_portfolio(&PORTFOLIO.replace("1 5 1", "1  5   1"), psplib::read_sm).is_ok());
+         assert!(portfolio::parse_portfolio(&PORTFOLIO.replace("1 5 1", "1 5 crew"), psplib::read_sm).is_err());
+         assert!(portfolio::parse_portfolio(&PORTFOLIO.replace("1 5 1", "1 1 1"), psplib::read_sm).is_err());
+         assert!(portfolio::parse_portfolio(&PORTFOLIO.replace("projects 2", "projects 3"), psplib::read_sm).is_err());
+     }
+ }
@@ -0,0 +1,232 @@
+use std::fs;
+
+use crate::prcpsp::project::Project as Project;
+use crate::prcpsp::rn_resource_usage::RnResourceUsage as RnResourceUsage;
+
+/*
+* Maximum number of interruptions of activities, which override the
+* number of interruptions given for the whole project, and constraints
+* on the segments (runs of contiguous subactivities) of interrupted activities.
+*
+* File format (lines starting with '#' are comments):
+*
+*   <activity> <max_interruptions> [<min_segment> [<max_gap>]]
+*
+* where activity is the id or name of an activity, 0 interruptions means the activity
+* can't be interrupted and -1 that it uses the number of the project. Each segment
+* lasts at least min_segment (0 if there isn't a minimum) and the gap between
+* consecutive segments is at most max_gap (-1 or without value if there isn't a maximum).
+*/
+
+/**
+* Read a file of maximum interruptions and segment constraints and set them to the activities of a project.
+* project: project whose activities are changed.
+* filename: path to the interruptions.
+*/
+pub fn read_interruptions(project: &mut Project, filename: &str) -> Result<(), String> {
+    let contents = fs::read_to_string(filename)
+            .map_err(|e| format!("No se pudo leer el archivo {}: {}", filename, e))?;
+    parse_interruptions(project, &contents)
+}
+
+/**
+* Set the maximum interruptions and segment constraints of a text to the activities of a project.
+* project: project whose activities are changed.
+* contents: text of the interruptions.
+*/
+pub fn parse_interruptions(project: &mut Project, contents: &str) -> Result<(), String> {
+    for line in contents.lines().map(|x| x.trim()).filter(|x| !x.is_empty() && !x.starts_with('#')) {
+        let tokens : Vec<&str> = line.split_whitespace().collect();
+        if tokens.len() < 2 || tokens.len() > 4 {
+            return Err(format!("Interrupciones incompletas: '{}'", line));
+        }
+        let number = |i: usize, default: i32| match tokens.get(i) {
+            Some(t) => t.parse::<i32>()
+                        .ok()
+                        .filter(|x| *x >= -1)
+                        .ok_or_else(|| format!("Valor invalido '{}' en '{}'", t, line)),
+            None => Ok(default),
+        };
+        let max_interruptions = number(1, -1)?;
+        let min_segment = number(2, 0)?;
+        if min_segment < 0 {
+            return Err(format!("Valor invalido '{}' en '{}'", min_segment, line));
+        }
+        let max_gap = number(3, -1)?;
+        let activity = project.activities.iter_mut()
+                                         .find(|x| x.id.to_string() == tokens[0] || x.name == tokens[0])
+                                         .ok_or_else(|| format!("Actividad {} no encontrada en '{}'", tokens[0], line))?;
+        activity.max_interruptions = max_interruptions;
+        activity.min_segment = min_segment as u32;
+        activity.max_gap = max_gap;
+    }
+    Ok(())
+}
+
+/*
+* Overhead of resuming activities after an interruption.
+*
+* File format (lines starting with '#' are comments):
+*
+*   <activity> <resume_time> [<resource> <usage> ..]
+*
+* where activity is the id or name of an activity, resume_time the time added to a
+* subactivity which doesn't start when the previous one finishes and each pair
+* resource (id or name of a renewable resource) and usage the units it also uses.
+*/
+
+/**
+* Read a file of resume overheads and set them to the activities of a project.
+* project: project whose activities are changed.
+* filename: path to the resume overheads.
+*/
+pub fn read_resume(project: &mut Project, filename: &str) -> Result<(), String> {
+    let contents = fs::read_to_string(filename)
+            .map_err(|e| format!("No se pudo leer el archivo {}: {}", filename, e))?;
+    parse_resume(project, &contents)
+}
+
+/**
+* Set the resume overheads of a text to the activities of a project.
+* project: project whose activities are changed.
+* contents: text of the resume overheads.
+*/
+pub fn parse_resume(project: &mut Project, contents: &str) -> Result<(), String> {
+    for line in contents.lines().map(|x| x.trim()).filter(|x| !x.is_empty() && !x.starts_with('#')) {
+        let tokens : Vec<&str> = line.split_whitespace().collect();
+        if tokens.len() < 2 || !tokens.len().is_multiple_of(2) {
+            return Err(format!("Reanudacion incompleta: '{}'", line));
+        }
+        let resume_time = tokens[1].parse::<u32>().map_err(|_| format!("Valor invalido '{}' en '{}'", tokens[1], line))?;
+        let mut resume_supplies : Vec<RnResourceUsage> = vec![];
+        for pair in tokens[2..].chunks(2) {
+            let resource = project.resources.iter()
+                                            .find(|x| x.name == pair[0] || x.id.to_string() == pair[0])
+                                            .ok_or_else(|| format!("Recurso {} no encontrado", pair[0]))?;
+            let usage = pair[1].parse::<u32>().map_err(|_| format!("Valor invalido '{}' en '{}'", pair[1], line))?;
+            if usage > 0 {
+                resume_supplies.push(RnResourceUsage::new(resource.clone(), usage));
+            }
+        }
+        let activity = project.activities.iter_mut()
+                                         .find(|x| x.id.to_string() == tokens[0] || x.name == tokens[0])
+                                         .ok_or_else(|| format!("Actividad {} no encontrada en '{}'", tokens[0], line))?;
+        for extra in &resume_supplies {
+            let usage = activity.modes.iter()
+                                      .flat_map(|x| x.supplies.iter())
+                                      .chain(activity.supplies.iter())
+                                      .filter(|x| x.resource == extra.resource)
+                                      .map(|x| x.usage)
+                                      .max()
+                                      .unwrap_or(0);
+            if usage + extra.usage > extra.resource.capacity {
+                return Err(format!("La demanda al reanudar del recurso {} es mayor a su capacidad en '{}'", extra.resource.name, line));
+            }
+        }
+        activity.resume_time = resume_time;
+        activity.resume_supplies = resume_supplies;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+ mod tests {
+     use crate::prcpsp::preemption as preemption;
+     use crate::prcpsp::psplib as psplib;
+     use crate::prcpsp::csv_project as csv_project;
+     use crate::prcpsp::sa_state::SaState as SaState;
+     use crate::prcpsp::validator as validator;
+     use crate::prcpsp::validator::Violation as Violation;
+     use crate::prcpsp::project as project;
+
+     #[test]
+     fn test_interruptions() {
+         let mut project = psplib::read_sm("examples/psplib-9.sm").unwrap();
+         preemption::parse_interruptions(&mut project, "# colado\n4 0\n7 4\n8 -1\n").unwrap();
+         let pre_emptive = project.pre_emptive_project(1);
+         let pieces = |parent: i32| pre_emptive.activities.iter().filter(|x| x.parent == parent).map(|x| x.duration).collect::<Vec<u32>>();
+         assert_eq!(pieces(4), vec![4]);
+         assert_eq!(pieces(7), vec![1,1,1,1,1]);
+         assert_eq!(pieces(8), vec![1,2]);
+         assert_eq!(pieces(2), vec![1]);
+
+         let piece = pre_emptive.activities.iter().find(|x| x.parent == 4).unwrap();
+         let first = pre_emptive.activities.iter().find(|x| x.parent == 7).unwrap();
+         assert_eq!(piece.predecessors, vec![1]);
+         assert!(first.predecessors.iter().all(|p| pre_emptive.activities.iter().any(|x| x.id == *p && x.parent == 6)));
+
+         let state = SaState::new(pre_emptive, 14);
+         assert!(validator::validate(&state.project, &state.planning, &state.times).is_empty());
+
+         assert!(preemption::parse_interruptions(&mut project, "4\n").is_err());
+         assert!(preemption::parse_interruptions(&mut project, "4 1 2 3 4\n").is_err());
+         assert!(preemption::parse_interruptions(&mut project, "4 -2\n").is_err());
+         assert!(preemption::parse_interruptions(&mut project, "4 1 -1\n").is_err());
+         assert!(preemption::parse_interruptions(&mut project, "99 1\n").is_err());
+     }
+
+     #[test]
+     fn test_resume() {
+         let resources = "name,capacity\ncrew,2\n";
+         let activities = "id,name,duration,predecessors,crew,interruptions\nA,Walls,2,,2,0\nB,Paint,2,,1,\n";
+         let mut project = csv_project::parse_csv(activities, resources).unwrap();
+         preemption::parse_resume(&mut project, "Paint 1 crew 1\n").unwrap();
+         let pre_emptive = project.pre_emptive_project(1);
+         assert_eq!(pre_emptive.activities.iter().map(|x| x.get_parent()).collect::<Vec<u32>>(), vec![1,2,3,3,4]);
+
+         // Walls interrupt paint, which takes one more time unit and another crew to resume
+         let state = SaState::from_planning(pre_emptive.clone(), vec![1,3,2,4,5], 14).unwrap();
+         assert_eq!(state.times, vec![0,0,1,3,5]);
+         let resumed = state.project.activities.iter().find(|x| x.id == 4).unwrap();
+         assert_eq!((resumed.duration, resumed.get_demand(), resumed.resumed), (2, 2, true));
+         assert!(validator::validate(&state.project, &state.planning, &state.times).is_empty());
+         assert!(!validator::validate(&pre_emptive, &state.planning, &[0,0,1,3,4]).is_empty());
+
+         let state = SaState::from_planning(state.project, vec![1,3,4,2,5], 14).unwrap();
+         assert_eq!(state.times, vec![0,0,1,2,4]);
+         assert!(state.project.activities.iter().all(|x| !x.resumed && x.duration <= 2));
+         assert!(validator::validate(&state.project, &state.planning, &state.times).is_empty());
+
+         assert!(preemption::parse_resume(&mut project, "Paint 1 crew\n").is_err());
+         assert!(preemption::parse_resume(&mut project, "Paint 1 crew 2\n").is_err());
+         assert!(preemption::parse_resume(&mut project, "Paint 1 cranes 1\n").is_err());
+     }
+
+     #[test]
+     fn test_segments() {
+         assert_eq!(project::segments(&[(4, 1), (0, 2), (2, 1), (3, 0), (6, 2)]), vec![(0, 3), (4, 5), (6, 8)]);
+         assert_eq!(project::segment_excess(&[(0, 3), (4, 5), (6, 8)], 2, -1), 1);
+         assert_eq!(project::segment_excess(&[(0, 3), (4, 5), (9, 11)], 0, 2), 2);
+         assert_eq!(project::segment_excess(&[(0, 1)], 3, 0), 0);
+
+         let mut project = psplib::read_sm("examples/psplib-9.sm").unwrap();
+         preemption::parse_interruptions(&mut project, "4 3 2 1\n7 4 2 0\n").unwrap();
+         let pre_emptive = project.pre_emptive_project(3);
+         let pieces = |parent: i32| pre_emptive.activities.iter().filter(|x| x.parent == parent).map(|x| x.duration).collect::<Vec<u32>>();
+         assert_eq!(pieces(4), vec![2,2]);
+         assert_eq!(pieces(7), vec![2,3]);
+
+         // Pieces of activity 4 with a gap of 2
+         let mut state = SaState::new(pre_emptive, 14);
+         let ids : Vec<u32> = state.project.activities.iter().filter(|x| x.parent == 4).map(|x| x.id).collect();
+         let i = state.planning.iter().position(|x| *x == ids[1]).unwrap();
+         state.times[i] = state.times[state.planning.iter().position(|x| *x == ids[0]).unwrap()] + 4;
+         let violations = validator::validate(&state.project, &state.planning, &state.times);
+         assert!(violations.iter().any(|x| matches!(x, Violation::Gap(4, _, _, 1))));
+
+         state.get_planning();
+         for activity in state.project.activities.iter_mut().filter(|x| x.parent == 4) {
+             activity.start_time = if activity.id == ids[0] { 0 } else { 5 };
+         }
+         assert_eq!(state.project.segment_excess(), 2);
+
+         // The second piece of Pour starts before Paint, which waits for Cure, to meet the gap,
+         // and Sand, after them in the list, still starts no earlier than Paint
+         let resources = "name,capacity\ncrew,2\n";
+         let activities = "id,name,duration,predecessors,crew,interruptions,max_gap\nA,Pour,2,,1,1,0\nC,Cure,3,,1,0,\nB,Paint,1,C,1,0,\nD,Sand,1,,1,0,\n";
+         let project = csv_project::parse_csv(activities, resources).unwrap().pre_emptive_project(1);
+         let state = SaState::from_planning(project, vec![1,2,4,5,3,6,7], 14).unwrap();
+         assert_eq!(state.times, vec![0,0,0,3,1,3,4]);
+         assert_eq!(state.project.segment_excess(), 0);
+     }
+ }
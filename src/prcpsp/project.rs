@@ -1,13 +1,23 @@
 use crate::prcpsp::activity::Activity as Activity;
 use crate::prcpsp::rn_resource::RnResource as RnResource;
+use crate::prcpsp::nr_resource::NrResource as NrResource;
+use crate::prcpsp::mode::Mode as Mode;
+use crate::prcpsp::relation::Relation as Relation;
+use crate::prcpsp::portfolio::Subproject as Subproject;
 use crate::prcpsp::rn_resource_usage::RnResourceUsage as RnResourceUsage;
+use crate::prcpsp::worker::Worker as Worker;
+use crate::prcpsp::worker as worker;
 
 
 /** Represents the project network for scheduling problems **/
 #[derive(Debug,Clone)]
 pub struct Project {
     pub activities : Vec<Activity>,
-    pub resources : Vec<RnResource>
+    pub resources : Vec<RnResource>,
+    pub nr_resources : Vec<NrResource>,
+    pub relations : Vec<Relation>,
+    pub subprojects : Vec<Subproject>,
+    pub workers : Vec<Worker>
 }
 
 /*
@@ -20,24 +30,51 @@ struct ActivitySubs(Activity, Vec<Activity>);
 impl Project {
 
     pub fn new(activities : Vec<Activity>, resources: Vec<RnResource>) -> Project {
-        Project { activities, resources }
+        Project { activities, resources, nr_resources: vec![], relations: vec![], subprojects: vec![], workers: vec![] }
     }
 
     /**
     * From the current project, create a new project where
-    * each activity is splitted in n subactivities.
+    * each activity is splitted in n+1 subactivities, or as many as
+    * its own maximum number of interruptions allows.
     */
     pub fn pre_emptive_project(&self, n: u32) -> Project {
         let pair_activities : Vec<ActivitySubs> = self.split_activities(n);
+        let relations = self.split_relations(&pair_activities);
         let subactivities : Vec<Activity> = self.set_neighbor_subactivities(pair_activities);
         let resources = self.resources.clone();
-        Project { activities: subactivities, resources }
+        let nr_resources = self.nr_resources.clone();
+        let subprojects = self.subprojects.clone();
+        let workers = self.workers.clone();
+        Project { activities: subactivities, resources, nr_resources, relations, subprojects, workers }
     }
 
     /**
-    * From the current activities, split each one in n+1 subactivities.
+    * Return the relations between subactivities, where the start of an activity
+    * is the start of its first subactivity and its finish the finish of the last one.
+    * pair_activities: activities and their subactivities.
+    */
+    fn split_relations(&self, pair_activities: &[ActivitySubs]) -> Vec<Relation> {
+        let subs = |id: u32| &pair_activities.iter().find(|x| x.0.id == id).unwrap().1;
+        self.relations.iter()
+                      .map(|r| {
+                          let from = if r.kind.is_from_start() { subs(r.from).first() } else { subs(r.from).last() };
+                          let to = if r.kind.is_to_start() { subs(r.to).first() } else { subs(r.to).last() };
+                          Relation::new(from.unwrap().id, to.unwrap().id, r.kind, r.min_lag, r.max_lag)
+                      })
+                      .collect()
+    }
+
+    /**
+    * From the current activities, split each one in n+1 subactivities,
+    * or in max_interruptions+1 if the activity has its own limit (0 if it can't be interrupted).
     * If n is greater than the activity's duration, then the activity
-    * is split in subactivities of duration 1.
+    * is split in subactivities of duration 1, or of min_segment if the activity has a minimum
+    * segment length. Every subactivity takes the minimum segment, maximum gap, resume overhead,
+    * weight, due date, cash flow and duration distribution of the activity.
+    * A milestone (activity of duration 0) has a single subactivity.
+    * Non-renewable resources are consumed by the first subactivity,
+    * which also takes the release time, and the last one takes the deadline.
     * Return a list of tuples, containing the original activity and its subactivities in a vector.
     * [ActivitySubs_1,..,ActivitySubs_n]
     */
@@ -51,21 +88,51 @@ impl Project {
         for i in 1..self.activities.len()-1 {
             let activity = self.activities[i].clone();
             let mut subs : Vec<Activity> = vec![];
-            let m = n+1;
-            let mut duration_sub : u32 = activity.duration / m;
-            let mut subs_n = m;
-            if duration_sub == 0 {
-                duration_sub = 1;
-                subs_n = activity.duration as u32;
+            let mut m = if activity.max_interruptions >= 0 { activity.max_interruptions as u32 + 1 } else { n+1 };
+            // Subactivities are at least as long as the minimum segment in every mode
+            if activity.min_segment > 0 {
+                let fit = activity.modes.iter()
+                                        .map(|x| x.duration)
+                                        .chain(std::iter::once(activity.duration))
+                                        .filter(|x| *x > 0)
+                                        .map(|x| (x / activity.min_segment).max(1))
+                                        .min()
+                                        .unwrap_or(1);
+                m = m.min(fit);
             }
-
-            for j in 0..subs_n {
-                let mut duration_sub_ = duration_sub;
-                if j == subs_n - 1 && activity.duration > m {
-                    duration_sub_ += activity.duration % m;
+            // Every mode is split in the same subactivities, as many as the longest mode allows
+            let longest = activity.modes.iter().map(|x| x.duration).fold(activity.duration, u32::max);
+            let subs_n = if longest < m { longest.max(1) } else { m };
+            let durations = split_duration(activity.duration, subs_n);
+            let modes_durations : Vec<Vec<u32>> = activity.modes.iter().map(|x| split_duration(x.duration, subs_n)).collect();
+
+            for j in 0..subs_n as usize {
+                let mut subactivity = Activity::new(subactivities_count, activity.id as i32, subactivities_count.to_string(), vec![], vec![], vec![], durations[j], -1);
+                if durations[j] > 0 {
+                    subactivity.supplies = activity.clone().supplies;
                 }
-                let mut subactivity = Activity::new(subactivities_count, activity.id as i32, subactivities_count.to_string(), vec![], vec![], vec![], duration_sub_, -1);
-                subactivity.supplies = activity.clone().supplies;
+                if j == 0 {
+                    subactivity.consumptions = activity.clone().consumptions;
+                    subactivity.release = activity.release;
+                }
+                if j == subs_n as usize - 1 {
+                    subactivity.deadline = activity.deadline;
+                }
+                subactivity.modes = activity.modes.iter()
+                                                  .zip(modes_durations.iter())
+                                                  .map(|(mode, d)| Mode::new(mode.id, d[j],
+                                                                             if d[j] > 0 { mode.supplies.clone() } else { vec![] },
+                                                                             if j == 0 { mode.consumptions.clone() } else { vec![] }))
+                                                  .collect();
+                subactivity.mode = activity.mode;
+                subactivity.min_segment = activity.min_segment;
+                subactivity.max_gap = activity.max_gap;
+                subactivity.resume_time = activity.resume_time;
+                subactivity.resume_supplies = activity.resume_supplies.clone();
+                subactivity.weight = activity.weight;
+                subactivity.due_date = activity.due_date;
+                subactivity.cash_flow = activity.cash_flow;
+                subactivity.distribution = activity.distribution.clone();
                 subactivities_count+=1;
                 if j > 0 {
                     let mut anteccessor = subs.pop().unwrap();
@@ -81,7 +148,9 @@ impl Project {
         }
 
         let last = self.activities[self.activities.len()-1].clone();
-        let last_subactivity = Activity::new(subactivities_count, last.id as i32,subactivities_count.to_string(),vec![],vec![],vec![],0,-1);
+        let mut last_subactivity = Activity::new(subactivities_count, last.id as i32,subactivities_count.to_string(),vec![],vec![],vec![],0,-1);
+        last_subactivity.release = last.release;
+        last_subactivity.deadline = last.deadline;
         pair_activities.push(ActivitySubs(last,vec![last_subactivity]));
 
         return pair_activities;
@@ -90,6 +159,7 @@ impl Project {
     /**
     * For each ActivitySubs tuple, set predecessors and successors of first
     * and last subactivity respectively from current activity.
+    * Activities can have a different number of subactivities, a single one if they can't be interrupted.
     * pair_activities: vector to set anteccessor and successor to subactivities.
     */
     fn set_neighbor_subactivities(&self, pair_activities: Vec<ActivitySubs>) -> Vec<Activity> {
@@ -116,11 +186,236 @@ impl Project {
         return subactivities;
     }
 
+    /**
+    * Select the execution mode of an activity of the original project,
+    * for the activity itself or for each of its subactivities.
+    * parent: id of the activity in the original project.
+    * mode: index of the mode.
+    */
+    pub fn set_mode(&mut self, parent: u32, mode: usize) {
+        for activity in self.activities.iter_mut().filter(|x| x.get_parent() == parent) {
+            activity.set_mode(mode);
+        }
+    }
+
+    /**
+    * Change the duration of an activity of the original project, split among its
+    * subactivities with positive duration as when the project was split.
+    * Resume overheads are removed, the decoder charges them again. The duration of the selected
+    * mode of a multi-mode activity is replaced until its mode is selected again.
+    * parent: id of the activity in the original project.
+    * duration: new duration.
+    */
+    pub fn set_duration(&mut self, parent: u32, duration: u32) {
+        let pieces : Vec<usize> = (0..self.activities.len()).filter(|i| self.activities[*i].get_parent() == parent).collect();
+        let mut positive : Vec<usize> = pieces.iter().copied().filter(|i| self.activities[*i].duration > 0).collect();
+        if positive.is_empty() {
+            positive = pieces.into_iter().take(1).collect();
+        }
+        for (i, d) in positive.iter().zip(split_duration(duration, positive.len() as u32)) {
+            self.activities[*i].set_resumed(false);
+            self.activities[*i].duration = d;
+        }
+    }
+
+    /**
+    * Return the ids in the original project of the activities with more than one mode.
+    */
+    pub fn multi_mode_activities(&self) -> Vec<u32> {
+        let mut parents : Vec<u32> = self.activities.iter().filter(|x| x.modes.len() > 1).map(|x| x.get_parent()).collect();
+        parents.dedup();
+        parents
+    }
+
+    /**
+    * Return the units of each non-renewable resource consumed by the activities.
+    */
+    pub fn nr_consumption(&self) -> Vec<(NrResource, u32)> {
+        self.nr_resources.iter()
+                         .map(|r| (r.clone(), self.activities.iter()
+                                                             .flat_map(|x| x.consumptions.iter())
+                                                             .filter(|x| x.resource == *r)
+                                                             .map(|x| x.usage)
+                                                             .sum()))
+                         .collect()
+    }
+
+    /**
+    * Return the units consumed over the capacity of non-renewable resources.
+    * A project is feasible for non-renewable resources if it's 0.
+    */
+    pub fn nr_excess(&self) -> u32 {
+        self.nr_consumption().iter().map(|(r, used)| used.saturating_sub(r.capacity)).sum()
+    }
+
+    /**
+    * Return the time units that planned activities miss the lags of relations.
+    * Relations are only checked if both activities are planned.
+    */
+    pub fn lag_excess(&self) -> u32 {
+        self.missed_relations().iter().map(|(_, excess)| excess).sum()
+    }
+
+    /**
+    * Return the relations whose lags planned activities miss, with the time units missed.
+    * Relations are only checked if both activities are planned.
+    */
+    pub fn missed_relations(&self) -> Vec<(Relation, u32)> {
+        self.relations.iter()
+                      .filter_map(|r| {
+                          let from = self.activities.iter().find(|x| x.id == r.from).unwrap();
+                          let to = self.activities.iter().find(|x| x.id == r.to).unwrap();
+                          if from.start_time == -1 || to.start_time == -1 {
+                              return None;
+                          }
+                          let excess = r.excess(from.start_time, from.duration, to.start_time, to.duration);
+                          if excess > 0 { Some((r.clone(), excess)) } else { None }
+                      })
+                      .collect()
+    }
+
+    /**
+    * Return the finish time of a project of the portfolio, the start time of its end activity.
+    * subproject: project of the portfolio.
+    */
+    pub fn subproject_finish(&self, subproject: &Subproject) -> i32 {
+        self.activities.iter()
+                       .filter(|x| x.get_parent() == subproject.end)
+                       .map(|x| x.start_time + x.duration as i32)
+                       .max()
+                       .unwrap_or(-1)
+    }
+
+    /**
+    * Return each activity of the original project, except the dummy start and end
+    * activities, as its first subactivity and the finish time of its last one.
+    */
+    pub fn activity_finishes(&self) -> Vec<(&Activity, i32)> {
+        let first = self.activities.first().map_or(0, |x| x.get_parent());
+        let last = self.activities.last().map_or(0, |x| x.get_parent());
+        let mut finishes : Vec<(&Activity, i32)> = vec![];
+        for activity in self.activities.iter().filter(|x| x.get_parent() != first && x.get_parent() != last) {
+            let finish = activity.start_time + activity.duration as i32;
+            match finishes.iter_mut().find(|(x, _)| x.get_parent() == activity.get_parent()) {
+                Some(pair) => pair.1 = pair.1.max(finish),
+                None => finishes.push((activity, finish)),
+            }
+        }
+        finishes
+    }
+
+    /**
+    * Return the usage of each renewable resource at each time from 0 to the horizon (not included)
+    * by the planned activities, in the order of the resources.
+    * horizon: time where the profile ends.
+    */
+    pub fn resource_profile(&self, horizon: u32) -> Vec<Vec<u32>> {
+        let mut profile = vec![vec![0; horizon as usize]; self.resources.len()];
+        for activity in self.activities.iter().filter(|x| x.start_time != -1) {
+            for supply in &activity.supplies {
+                let r = match self.resources.iter().position(|x| *x == supply.resource) {
+                    Some(r) => r,
+                    None => continue,
+                };
+                let start = activity.start_time.max(0) as u32;
+                for t in start..(start + activity.duration).min(horizon) {
+                    profile[r][t as usize] += supply.usage;
+                }
+            }
+        }
+        profile
+    }
+
+    /**
+    * Return the cost of the capacity of the renewable resources, as the sum of
+    * the capacity of each resource times its unit cost.
+    */
+    pub fn capacity_cost(&self) -> u32 {
+        self.resources.iter().map(|x| x.capacity * x.cost).sum()
+    }
+
+    /**
+    * Return the range (lowest, highest) of capacities of a resource to search, where the lowest
+    * is the largest usage of an activity in any of its modes, also when it's resumed after an
+    * interruption, and the highest the capacity to execute every activity at the same time
+    * or the current capacity if it's higher.
+    * resource: resource to check.
+    */
+    pub fn capacity_bounds(&self, resource: &RnResource) -> (u32, u32) {
+        let mut lowest = 0;
+        let mut usages : Vec<(u32, u32)> = vec![];
+        for activity in &self.activities {
+            let mut current = activity.clone();
+            for mode in 0..activity.modes.len().max(1) {
+                current.set_mode(mode);
+                for resumed in &[false, true] {
+                    current.set_resumed(*resumed);
+                    let usage = current.supplies.iter().filter(|x| x.resource == *resource).map(|x| x.usage).sum();
+                    lowest = lowest.max(usage);
+                    match usages.iter_mut().find(|(parent, _)| *parent == activity.get_parent()) {
+                        Some(highest) => highest.1 = highest.1.max(usage),
+                        None => usages.push((activity.get_parent(), usage)),
+                    }
+                }
+            }
+        }
+        let highest = usages.iter().map(|x| x.1).sum::<u32>().max(resource.capacity).max(lowest);
+        (lowest, highest)
+    }
+
+    /**
+    * Return the resources whose capacity is chosen by the search, the ones with a unit cost
+    * that aren't skills of workers and have more than one capacity to choose,
+    * as their index and the range (lowest, highest) of their capacities.
+    */
+    pub fn adjustable_resources(&self) -> Vec<(usize, u32, u32)> {
+        (0..self.resources.len()).filter(|r| self.resources[*r].cost > 0 && !self.is_skill(&self.resources[*r]))
+                                 .map(|r| {
+                                     let (lowest, highest) = self.capacity_bounds(&self.resources[r]);
+                                     (r, lowest, highest)
+                                 })
+                                 .filter(|(_, lowest, highest)| lowest < highest)
+                                 .collect()
+    }
+
+    /**
+    * Return the time units that planned activities finish after their deadlines.
+    */
+    pub fn deadline_excess(&self) -> u32 {
+        self.activities.iter().map(|x| x.get_lateness()).sum()
+    }
+
+    /**
+    * Return the time units that planned activities miss their segment constraints,
+    * as segments shorter than the minimum or gaps between segments longer than the maximum.
+    * A segment is a run of contiguous subactivities of the same activity.
+    */
+    pub fn segment_excess(&self) -> u32 {
+        let mut parents : Vec<u32> = self.activities.iter().map(|x| x.get_parent()).collect();
+        parents.dedup();
+        parents.iter().map(|parent| self.activity_segment_excess(*parent)).sum()
+    }
+
+    /**
+    * Return the time units that the subactivities of an activity miss its segment
+    * constraints, 0 if any of them isn't planned.
+    * parent: id of the activity in the original project.
+    */
+    pub fn activity_segment_excess(&self, parent: u32) -> u32 {
+        let pieces : Vec<&Activity> = self.activities.iter().filter(|x| x.get_parent() == parent).collect();
+        if pieces.is_empty() || pieces.iter().any(|x| x.start_time == -1) {
+            return 0;
+        }
+        let times : Vec<(i32, u32)> = pieces.iter().map(|x| (x.start_time, x.duration)).collect();
+        segment_excess(&segments(&times), pieces[0].min_segment, pieces[0].max_gap)
+    }
+
     /**
     * Given an activity, set its start_time in project.
     * activity: activity to change start_time.
     * time: time to assign
     */
+    #[allow(dead_code)]
     pub fn set_time(&mut self, activity: Activity, time: i32) {
         let get_index = self.activities.iter().position(|x| x.clone() == activity);
         match get_index {
@@ -133,6 +428,8 @@ impl Project {
     * Get the time where activity can be planned.
     * Return the maximum between the latest end time of predecessors
     * in current activity and the current time.
+    * Minimum lags of relations from planned activities and the release
+    * time of the activity are also respected.
     * activity: activity to check.
     * time: time where activity can be planned.
     */
@@ -142,8 +439,8 @@ impl Project {
             return time;
         }
         let mut max_time_predecessor : i32 = 0;
-        for pre in activity.predecessors {
-            let predecessor = self.activities.iter().find(|x|x.id == pre).unwrap();
+        for pre in &activity.predecessors {
+            let predecessor = self.activities.iter().find(|x|x.id == *pre).unwrap();
             if predecessor.start_time == -1 {
                 panic!("Predecessor should be planned");
             }
@@ -152,12 +449,134 @@ impl Project {
                 max_time_predecessor = end_time;
             }
         }
+        for relation in self.relations.iter().filter(|x| x.to == activity.id) {
+            let from = self.activities.iter().find(|x| x.id == relation.from).unwrap();
+            if from.start_time != -1 {
+                let earliest = relation.earliest_start(from.start_time, from.duration, activity.duration);
+                if earliest > max_time_predecessor {
+                    max_time_predecessor = earliest;
+                }
+            }
+        }
+        if max_time_predecessor < activity.release {
+            max_time_predecessor = activity.release;
+        }
         if max_time_predecessor < time {
             max_time_predecessor = time;
         }
         return max_time_predecessor;
     }
 
+    /**
+    * Return true if a resource is a skill of the workers.
+    * resource: resource to check.
+    */
+    pub fn is_skill(&self, resource: &RnResource) -> bool {
+        self.workers.iter().any(|x| x.level(resource).is_some())
+    }
+
+    /**
+    * Return the supplies of an activity on skills of the workers,
+    * none for activities of duration 0.
+    * activity: activity to check.
+    */
+    pub fn skill_requirements(&self, activity: &Activity) -> Vec<RnResourceUsage> {
+        if activity.duration == 0 {
+            return vec![];
+        }
+        activity.supplies.iter().filter(|x| self.is_skill(&x.resource)).cloned().collect()
+    }
+
+    /**
+    * Assign to an activity planned at a time workers with the skills it needs that
+    * aren't assigned to other planned activities executed at the same time.
+    * Return false if there aren't enough free workers, true if the project doesn't have workers.
+    * activity: activity to assign.
+    * time: time where activity is planned.
+    */
+    pub fn assign_workers(&self, activity: &mut Activity, time: i32) -> bool {
+        if self.workers.is_empty() {
+            return true;
+        }
+        let end = time + activity.duration as i32;
+        let busy : Vec<u32> = self.activities.iter()
+                                             .filter(|x| x.start_time != -1 && *x != activity && x.start_time < end && time < x.start_time + x.duration as i32)
+                                             .flat_map(|x| x.workers.iter().copied())
+                                             .collect();
+        let free : Vec<&Worker> = self.workers.iter().filter(|x| !busy.contains(&x.id)).collect();
+        match worker::assign(&free, &self.skill_requirements(activity)) {
+            Some(assigned) => {
+                activity.workers = assigned;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /**
+    * Return the subactivity of the same activity executed just before the given one,
+    * None if it's the first one or it isn't a subactivity.
+    * activity: subactivity to check.
+    */
+    pub fn previous_piece(&self, activity: &Activity) -> Option<&Activity> {
+        self.activities.iter().find(|x| x.get_parent() == activity.get_parent() && activity.predecessors.contains(&x.id))
+    }
+
+    /**
+    * Plan an activity at the earliest time from the given one where its predecessors
+    * have finished, resources are available and free workers have the skills it needs,
+    * and return that time.
+    * A subactivity that doesn't start when the previous one finishes is resumed after
+    * an interruption, taking its resume overhead in duration and resources.
+    * A subactivity whose activity has a maximum gap starts before the given time if it's
+    * needed to meet the gap, and later only if it can't start within the gap.
+    * activity: activity to plan.
+    * time: time where activity can be planned.
+    */
+    pub fn plan_activity(&mut self, activity: &Activity, time: i32) -> i32 {
+        let mut current = activity.clone();
+        current.set_resumed(false);
+        let mut time = self.get_time_planning(current.clone(), time);
+        let latest = match self.previous_piece(&current) {
+            Some(x) if current.duration > 0 && current.max_gap >= 0 && x.start_time != -1 => Some(x.start_time + x.duration as i32 + current.max_gap),
+            _ => None,
+        };
+        if let Some(latest) = latest.filter(|x| time > *x) {
+            let earliest = self.get_time_planning(current, 0);
+            if let Some(start) = (earliest..=latest).find(|t| self.plan_activity_at(activity, *t)) {
+                return start;
+            }
+        }
+        while !self.plan_activity_at(activity, time) {
+            time += 1;
+        }
+        time
+    }
+
+    /**
+    * Plan an activity at a time if resources are available and free workers have the skills
+    * it needs then, resuming it as plan_activity does. Return true if the activity is planned.
+    * Predecessors should have finished by that time.
+    * activity: activity to plan.
+    * time: time where activity is planned.
+    */
+    pub fn plan_activity_at(&mut self, activity: &Activity, time: i32) -> bool {
+        let mut current = activity.clone();
+        current.set_resumed(false);
+        let previous = match self.previous_piece(&current) {
+            Some(x) if current.duration > 0 => Some(x.start_time + x.duration as i32),
+            _ => None,
+        };
+        current.set_resumed(previous.is_some_and(|finish| time > finish));
+        if self.resource_conflict(current.clone(), time) || !self.assign_workers(&mut current, time) {
+            return false;
+        }
+        current.start_time = time;
+        let index = self.activities.iter().position(|x| *x == current).unwrap();
+        self.activities[index] = current;
+        true
+    }
+
     /**
     * Given an activity, check if all its predecessors have been planned.
     * An activity is planned if its start_time isn't -1.
@@ -176,40 +595,91 @@ impl Project {
     /**
     * Check if a conflict occurs when try to planning
     * an activity in a specified time.
+    * The capacity of each resource is checked at every time the activity
+    * is executed, as it can change in periods of the resource.
     * activity: activity to check.
     * time: time where activity can be planned.
     */
     pub fn resource_conflict(&self, activity: Activity, time: i32) -> bool {
-        let activities_time : Vec<Activity> = self.activities.clone()
-                                                             .into_iter()
-                                                             .filter(|x| x.start_time != -1 && *x  != activity && (x.start_time == time ||
-                                                                        (x.start_time < time && time < x.start_time + x.duration as i32)) ).collect();
-        if activities_time.len() == 0 {
-            return false;
-        }
-
-        let supplies_used : Vec<Vec<RnResourceUsage>> = activities_time.iter().map(|x| x.supplies.clone() ).collect();
-        let resources_used : Vec<RnResource> = activity.supplies.iter().map(|x|x.resource.clone()).collect();
-        for resource in resources_used {
-            let mut demand = 0;
-            for supplies in &supplies_used {
-                for supply in supplies {
-                    if supply.resource == resource {
-                        demand += supply.usage;
-                    }
+        let end = time + activity.duration.max(1) as i32;
+        for supply in &activity.supplies {
+            let resource = self.resources.iter().find(|x| **x == supply.resource).unwrap_or(&supply.resource);
+            for t in time..end {
+                let demand : u32 = self.activities.iter()
+                                                  .filter(|x| x.start_time != -1 && **x != activity && (x.start_time == t ||
+                                                             (x.start_time < t && t < x.start_time + x.duration as i32)))
+                                                  .flat_map(|x| x.supplies.iter())
+                                                  .filter(|x| x.resource == supply.resource)
+                                                  .map(|x| x.usage)
+                                                  .sum();
+                if demand + supply.usage > resource.capacity_at(t) {
+                    return true;
                 }
             }
-            let supply = activity.supplies.iter().find(|&x|x.resource == resource).unwrap();
-            let usage = supply.usage;
-            if demand + usage  >  resource.capacity {
-                return true;
-            }
         }
-        return false;
+        false
     }
 
 }
 
+/**
+* Split a duration among subactivities. If the duration is shorter than the
+* number of subactivities, the first ones take one unit and the rest none,
+* otherwise the last one takes the remainder.
+* duration: duration to split.
+* subs_n: number of subactivities.
+*/
+fn split_duration(duration: u32, subs_n: u32) -> Vec<u32> {
+    if subs_n == 0 {
+        return vec![];
+    }
+    if duration < subs_n {
+        return (0..subs_n).map(|j| if j < duration { 1 } else { 0 }).collect();
+    }
+    let mut durations = vec![duration / subs_n; subs_n as usize];
+    if let Some(last) = durations.last_mut() {
+        *last += duration % subs_n;
+    }
+    durations
+}
+
+/**
+* Return the segments (start, finish) of an activity from the start time and duration
+* of its subactivities, joining subactivities where one starts when another finishes.
+* Subactivities of duration 0 don't form segments.
+* pieces: start time and duration of each subactivity.
+*/
+pub fn segments(pieces: &[(i32, u32)]) -> Vec<(i32, i32)> {
+    let mut pieces : Vec<(i32, u32)> = pieces.iter().filter(|x| x.1 > 0).copied().collect();
+    pieces.sort();
+    let mut segments : Vec<(i32, i32)> = vec![];
+    for (start, duration) in pieces {
+        match segments.last_mut() {
+            Some(last) if last.1 == start => last.1 += duration as i32,
+            _ => segments.push((start, start + duration as i32)),
+        }
+    }
+    segments
+}
+
+/**
+* Return the time units that segments of an activity miss its constraints, as the units
+* a segment is shorter than the minimum (or the whole activity if it's shorter) and the
+* units a gap between consecutive segments is longer than the maximum.
+* segments: segments of the activity in order.
+* min_segment: minimum length of a segment, 0 if there isn't one.
+* max_gap: maximum gap between segments, -1 if there isn't one.
+*/
+pub fn segment_excess(segments: &[(i32, i32)], min_segment: u32, max_gap: i32) -> u32 {
+    let total : i32 = segments.iter().map(|(start, finish)| finish - start).sum();
+    let needed = total.min(min_segment as i32);
+    let short : i32 = segments.iter().map(|(start, finish)| (needed - (finish - start)).max(0)).sum();
+    let long : i32 = if max_gap < 0 { 0 } else {
+        segments.windows(2).map(|x| (x[1].0 - x[0].1 - max_gap).max(0)).sum()
+    };
+    (short + long) as u32
+}
+
 
 
 #[cfg(test)]
@@ -0,0 +1,367 @@
+use std::fs;
+
+use crate::prcpsp::project::Project as Project;
+use crate::prcpsp::activity::Activity as Activity;
+use crate::prcpsp::rn_resource::RnResource as RnResource;
+use crate::prcpsp::rn_resource_usage::RnResourceUsage as RnResourceUsage;
+use crate::prcpsp::nr_resource::NrResource as NrResource;
+use crate::prcpsp::nr_resource_usage::NrResourceUsage as NrResourceUsage;
+use crate::prcpsp::mode::Mode as Mode;
+
+/**
+* Sections of a PSPLIB file that are read.
+*/
+#[derive(Debug,Clone,Copy,PartialEq)]
+enum Section {
+    Header,
+    Precedence,
+    Requests,
+    Availabilities,
+}
+
+/**
+* Read a PSPLIB instance, single-mode (.sm files of j30, j60, j90 and j120 sets)
+* or multi-mode (.mm files of j10 to j30 sets).
+* filename: path to the instance.
+*/
+pub fn read_sm(filename: &str) -> Result<Project, String> {
+    let contents = fs::read_to_string(filename)
+            .map_err(|e| format!("No se pudo leer el archivo {}: {}", filename, e))?;
+    parse_sm(&contents)
+}
+
+/**
+* Create a project from the contents of a PSPLIB file.
+* Job 1 is the dummy source and the last job is the dummy sink,
+* as the decoder in SaState expects.
+* Jobs with several modes start in their first mode.
+* contents: text of the instance.
+*/
+pub fn parse_sm(contents: &str) -> Result<Project, String> {
+    let mut section = Section::Header;
+    let mut jobs : usize = 0;
+    let mut renewable : usize = 0;
+    let mut nonrenewable : usize = 0;
+    let mut successors : Vec<(u32, Vec<u32>)> = vec![];
+    let mut requests : Vec<(u32, u32, Vec<u32>)> = vec![];
+    let mut modes : Vec<(u32, u32, Vec<u32>)> = vec![];
+    let mut capacities : Vec<u32> = vec![];
+
+    for line in contents.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('*') || (line.starts_with('-') && !line.contains(':')) {
+            continue;
+        }
+        if line.starts_with("PRECEDENCE RELATIONS") {
+            section = Section::Precedence;
+            continue;
+        }
+        if line.starts_with("REQUESTS/DURATIONS") {
+            section = Section::Requests;
+            continue;
+        }
+        if line.starts_with("RESOURCEAVAILABILITIES") {
+            section = Section::Availabilities;
+            continue;
+        }
+        if line.starts_with("PROJECT INFORMATION") {
+            section = Section::Header;
+            continue;
+        }
+        match section {
+            Section::Header => {
+                if line.starts_with("jobs") {
+                    jobs = header_value(line)? as usize;
+                } else if line.starts_with("- renewable") {
+                    renewable = header_value(line)? as usize;
+                } else if line.starts_with("- nonrenewable") {
+                    nonrenewable = header_value(line)? as usize;
+                }
+            }
+            Section::Precedence => {
+                if line.starts_with("jobnr") {
+                    continue;
+                }
+                let values = numbers(line)?;
+                if values.len() < 3 {
+                    return Err(format!("Relacion de precedencia incompleta: '{}'", line));
+                }
+                let count = values[2] as usize;
+                if values.len() - 3 != count {
+                    return Err(format!("La actividad {} declara {} sucesores pero tiene {}", values[0], count, values.len() - 3));
+                }
+                successors.push((values[0], values[3..].to_vec()));
+            }
+            Section::Requests => {
+                if line.starts_with("jobnr") {
+                    continue;
+                }
+                let values = numbers(line)?;
+                let columns = renewable + nonrenewable;
+                // Other modes of a job don't repeat its number
+                if values.len() == columns + 3 {
+                    requests.push((values[0], values[2], values[3..3 + renewable].to_vec()));
+                    modes.push((values[0], values[2], values[3..].to_vec()));
+                } else if values.len() == columns + 2 && !requests.is_empty() {
+                    let job = requests.last().unwrap().0;
+                    modes.push((job, values[1], values[2..].to_vec()));
+                } else {
+                    return Err(format!("Se esperaban {} recursos en '{}'", columns, line));
+                }
+            }
+            Section::Availabilities => {
+                if line.starts_with('R') || line.starts_with('N') {
+                    continue;
+                }
+                capacities = numbers(line)?;
+            }
+        }
+    }
+
+    if jobs == 0 || successors.len() != jobs || requests.len() != jobs {
+        return Err(format!("Se esperaban {} actividades, se encontraron {} relaciones y {} duraciones", jobs, successors.len(), requests.len()));
+    }
+    if capacities.len() < renewable + nonrenewable {
+        return Err(format!("Se esperaban {} capacidades de recursos, se encontraron {}", renewable + nonrenewable, capacities.len()));
+    }
+
+    let resources : Vec<RnResource> = (0..renewable).map(|k| RnResource::new(k as u32 + 1, (k + 1).to_string(), capacities[k])).collect();
+    let nr_resources : Vec<NrResource> = (0..nonrenewable).map(|k| NrResource::new(k as u32 + 1, (k + 1).to_string(), capacities[renewable + k])).collect();
+    let mut activities = build_activities(&successors, &requests, &resources)?;
+    for activity in &mut activities {
+        let job_modes : Vec<&(u32, u32, Vec<u32>)> = modes.iter().filter(|x| x.0 == activity.id).collect();
+        activity.consumptions = consumptions(&job_modes[0].2[renewable..], &nr_resources);
+        if job_modes.len() > 1 {
+            activity.modes = job_modes.iter()
+                                      .enumerate()
+                                      .map(|(i, (_, duration, usages))| Mode::new(i as u32 + 1, *duration, supplies(&usages[..renewable], &resources),
+                                                                                  consumptions(&usages[renewable..], &nr_resources)))
+                                      .collect();
+            activity.set_mode(0);
+        }
+    }
+    let mut project = Project::new(activities, resources);
+    project.nr_resources = nr_resources;
+    Ok(project)
+}
+
+/**
+* Create the activities of a project given its successors and requests.
+* successors: (job, successors) for each job.
+* requests: (job, duration, usage per resource) for each job.
+* resources: renewable resources in project.
+*/
+pub fn build_activities(successors: &[(u32, Vec<u32>)], requests: &[(u32, u32, Vec<u32>)], resources: &[RnResource]) -> Result<Vec<Activity>, String> {
+    let jobs = successors.len() as u32;
+    let mut activities : Vec<Activity> = vec![];
+    for (id, duration, usages) in requests {
+        if *id == 0 || *id > jobs {
+            return Err(format!("Actividad {} fuera de rango", id));
+        }
+        activities.push(Activity::new(*id, -1, id.to_string(), vec![], vec![], supplies(usages, resources), *duration, -1));
+    }
+    activities.sort();
+    if activities.iter().enumerate().any(|(i, x)| x.id != i as u32 + 1) {
+        return Err(String::from("Actividades repetidas en las duraciones"));
+    }
+
+    for (id, succs) in successors {
+        for s in succs {
+            if *s == 0 || *s > jobs || s == id {
+                return Err(format!("Sucesor {} de la actividad {} fuera de rango", s, id));
+            }
+            activities[(*id - 1) as usize].add_successor(*s);
+            activities[(*s - 1) as usize].add_anteccessor(*id);
+        }
+    }
+
+    if !activities.first().unwrap().predecessors.is_empty() || !activities.last().unwrap().successors.is_empty() {
+        return Err(String::from("Actividades ficticias de inicio y fin no encontradas"));
+    }
+    Ok(activities)
+}
+
+/**
+* Return the usages of resources greater than zero.
+* usages: usage of each resource.
+* resources: renewable resources in project.
+*/
+fn supplies(usages: &[u32], resources: &[RnResource]) -> Vec<RnResourceUsage> {
+    usages.iter()
+          .zip(resources.iter())
+          .filter(|(usage, _)| **usage > 0)
+          .map(|(usage, resource)| RnResourceUsage::new(resource.clone(), *usage))
+          .collect()
+}
+
+/**
+* Return the consumptions of non-renewable resources greater than zero.
+* usages: consumption of each resource.
+* nr_resources: non-renewable resources in project.
+*/
+fn consumptions(usages: &[u32], nr_resources: &[NrResource]) -> Vec<NrResourceUsage> {
+    usages.iter()
+          .zip(nr_resources.iter())
+          .filter(|(usage, _)| **usage > 0)
+          .map(|(usage, resource)| NrResourceUsage::new(resource.clone(), *usage))
+          .collect()
+}
+
+/**
+* Return the value of a header line as 'jobs (incl. supersource/sink ):  32'.
+*/
+fn header_value(line: &str) -> Result<u32, String> {
+    let value = line.split(':').nth(1).and_then(|x| x.split_whitespace().next());
+    match value {
+        Some(v) => v.parse::<u32>().map_err(|_| format!("Valor invalido en '{}'", line)),
+        None => Err(format!("Valor no encontrado en '{}'", line)),
+    }
+}
+
+/**
+* Parse every token in a line as an integer.
+*/
+pub fn numbers(line: &str) -> Result<Vec<u32>, String> {
+    line.split_whitespace()
+        .map(|x| x.parse::<u32>().map_err(|_| format!("Valor invalido '{}' en '{}'", x, line)))
+        .collect()
+}
+
+#[cfg(test)]
+ mod tests {
+     use crate::prcpsp::psplib as psplib;
+     use crate::prcpsp::sa_state::SaState as SaState;
+
+     const INSTANCE : &str = "************************************************************************
+file with basedata            : example.bas
+initial value random generator: 11
+************************************************************************
+projects                      :  1
+jobs (incl. supersource/sink ):  9
+horizon                       :  19
+RESOURCES
+  - renewable                 :  1   R
+  - nonrenewable              :  0   N
+  - doubly constrained        :  0   D
+************************************************************************
+PROJECT INFORMATION:
+pronr.  #jobs rel.date duedate tardcost  MPM-Time
+    1      7      0       10        1       10
+************************************************************************
+PRECEDENCE RELATIONS:
+jobnr.    #modes  #successors   successors
+   1        1          4           2   3   4   5
+   2        1          1           6
+   3        1          1           9
+   4        1          1           9
+   5        1          1           8
+   6        1          1           7
+   7        1          1           9
+   8        1          1           9
+   9        1          0
+************************************************************************
+REQUESTS/DURATIONS:
+jobnr. mode duration  R 1
+------------------------------------------------------------------------
+  1      1     0       0
+  2      1     1       1
+  3      1     2       2
+  4      1     4       2
+  5      1     3       2
+  6      1     1       2
+  7      1     5       1
+  8      1     3       2
+  9      1     0       0
+************************************************************************
+RESOURCEAVAILABILITIES:
+  R 1
+    5
+************************************************************************
+";
+
+     #[test]
+     fn test_parse_sm() {
+         let project = psplib::parse_sm(INSTANCE).unwrap();
+         assert_eq!(project.activities.len(), 9);
+         assert_eq!(project.resources.len(), 1);
+         assert_eq!(project.resources[0].capacity, 5);
+         let last = project.activities.last().unwrap();
+         assert_eq!(last.predecessors, vec![3,4,7,8]);
+         assert_eq!(project.activities[6].duration, 5);
+         assert_eq!(project.activities[6].get_demand(), 1);
+         assert!(project.activities[0].supplies.is_empty());
+
+         let state = SaState::new(project, 11);
+         assert_eq!(state.planning, vec![1,2,3,4,5,6,8,7,9]);
+         assert_eq!(state.times, vec![0,0,0,0,2,4,5,5,10]);
+     }
+
+     const MULTI_MODE : &str = "************************************************************************
+file with basedata            : example.bas
+initial value random generator: 7
+************************************************************************
+projects                      :  1
+jobs (incl. supersource/sink ):  4
+horizon                       :  10
+RESOURCES
+  - renewable                 :  1   R
+  - nonrenewable              :  1   N
+  - doubly constrained        :  0   D
+************************************************************************
+PRECEDENCE RELATIONS:
+jobnr.    #modes  #successors   successors
+   1        1          2           2   3
+   2        2          1           4
+   3        2          1           4
+   4        1          0
+************************************************************************
+REQUESTS/DURATIONS:
+jobnr. mode duration  R 1  N 1
+------------------------------------------------------------------------
+  1      1     0       0    0
+  2      1     4       2    3
+         2     2       4    5
+  3      1     3       2    1
+         2     1       3    4
+  4      1     0       0    0
+************************************************************************
+RESOURCEAVAILABILITIES:
+  R 1  N 1
+    4    8
+************************************************************************
+";
+
+     #[test]
+     fn test_parse_mm() {
+         let mut project = psplib::parse_sm(MULTI_MODE).unwrap();
+         assert_eq!(project.resources.len(), 1);
+         assert_eq!(project.resources[0].capacity, 4);
+         assert_eq!(project.activities[1].modes.len(), 2);
+         assert_eq!(project.activities[2].modes[1].duration, 1);
+         assert_eq!(project.activities[1].duration, 4);
+         assert!(project.activities[0].modes.is_empty());
+         assert_eq!(project.multi_mode_activities(), vec![2,3]);
+         assert_eq!(project.nr_resources.len(), 1);
+         assert_eq!(project.nr_resources[0].capacity, 8);
+         assert_eq!(project.activities[1].consumptions[0].usage, 3);
+         assert_eq!(project.nr_excess(), 0);
+
+         let state = SaState::new(project.clone(), 3);
+         assert_eq!(state.get_makespan(), 4);
+         project.set_mode(2, 1);
+         assert_eq!(project.activities[1].duration, 2);
+         assert_eq!(project.activities[1].get_demand(), 4);
+         let state = SaState::new(project.clone(), 3);
+         assert_eq!(state.get_makespan(), 5);
+         project.set_mode(3, 1);
+         assert_eq!(project.nr_excess(), 1);
+     }
+
+     #[test]
+     fn test_parse_sm_errors() {
+         let wrong_successors = INSTANCE.replace("   5        1          1           8", "   5        1          2           8");
+         assert!(psplib::parse_sm(&wrong_successors).is_err());
+         let missing_resource = INSTANCE.replace("  4      1     4       2", "  4      1     4");
+         assert!(psplib::parse_sm(&missing_resource).is_err());
+     }
+ }
@@ -4,6 +4,8 @@ use crate::prcpsp::project::Project as Project;
 use crate::prcpsp::activity::Activity as Activity;
 use crate::prcpsp::rn_resource::RnResource as RnResource;
 use crate::prcpsp::rn_resource_usage::RnResourceUsage as RnResourceUsage;
+use crate::prcpsp::nr_resource::NrResource as NrResource;
+use crate::prcpsp::nr_resource_usage::NrResourceUsage as NrResourceUsage;
 
 /**
 * Create a random RCPSP instance.
@@ -174,6 +176,30 @@ pub fn get_random_rcpsp(random_seed: u64, random_cost: u32, random_activities: u
     return project;
 }
 
+/**
+* Add random non-renewable resources to a project. Each activity consumes
+* between 0 and 9 units of each resource, and the capacity of a resource is
+* its total consumption, so the constructed optimum stays feasible.
+* project: project created by get_random_rcpsp.
+* random_seed: seed for pseudo-random numbers.
+* random_nr_resources: non-renewable resources number for project.
+*/
+pub fn add_nr_resources(project: &mut Project, random_seed: u64, random_nr_resources: u32) {
+    let mut rng : StdRng = SeedableRng::seed_from_u64(random_seed);
+    let last = project.activities.len() - 1;
+    for i in 1..=random_nr_resources {
+        let mut resource = NrResource::new(i, i.to_string(), 0);
+        let consumptions : Vec<u32> = (1..last).map(|_| rng.gen_range(0, 10)).collect();
+        resource.capacity = consumptions.iter().sum();
+        for (activity, consumption) in project.activities[1..last].iter_mut().zip(consumptions) {
+            if consumption > 0 {
+                activity.consumptions.push(NrResourceUsage::new(resource.clone(), consumption));
+            }
+        }
+        project.nr_resources.push(resource);
+    }
+}
+
 /**
 * Return the intervals for resources capacity,
 * where each resource has capacity according to its interval.
@@ -0,0 +1,208 @@
+use std::fmt;
+use std::fs;
+
+use crate::prcpsp::project::Project as Project;
+
+/**
+* Kind of a generalized precedence relation, the time of the first
+* activity (start or finish) and the time of the second one.
+*/
+#[derive(Debug,Clone,Copy,PartialEq)]
+pub enum RelationType {
+    StartStart,
+    StartFinish,
+    FinishStart,
+    FinishFinish,
+}
+
+impl RelationType {
+    /**
+    * Return the type from its abbreviation, as SS or FS.
+    */
+    pub fn from_str(value: &str) -> Option<RelationType> {
+        match value.to_uppercase().as_str() {
+            "SS" => Some(RelationType::StartStart),
+            "SF" => Some(RelationType::StartFinish),
+            "FS" => Some(RelationType::FinishStart),
+            "FF" => Some(RelationType::FinishFinish),
+            _ => None,
+        }
+    }
+
+    /**
+    * Check if the relation starts from the start time of the first activity.
+    */
+    pub fn is_from_start(&self) -> bool {
+        *self == RelationType::StartStart || *self == RelationType::StartFinish
+    }
+
+    /**
+    * Check if the relation goes to the start time of the second activity.
+    */
+    pub fn is_to_start(&self) -> bool {
+        *self == RelationType::StartStart || *self == RelationType::FinishStart
+    }
+}
+
+impl fmt::Display for RelationType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RelationType::StartStart => write!(f, "SS"),
+            RelationType::StartFinish => write!(f, "SF"),
+            RelationType::FinishStart => write!(f, "FS"),
+            RelationType::FinishFinish => write!(f, "FF"),
+        }
+    }
+}
+
+/**
+* Generalized precedence relation between two activities with time lags:
+* min_lag <= time(to) - time(from) <= max_lag, where the time of each activity
+* is its start or finish according to the kind of relation.
+*/
+#[derive(Debug,Clone,PartialEq)]
+pub struct Relation {
+    pub from: u32,
+    pub to: u32,
+    pub kind: RelationType,
+    pub min_lag: i32,
+    pub max_lag: Option<i32>,
+}
+
+impl Relation {
+    pub fn new(from: u32, to: u32, kind: RelationType, min_lag: i32, max_lag: Option<i32>) -> Relation {
+        Relation { from, to, kind, min_lag, max_lag }
+    }
+
+    /**
+    * Return the earliest start of the second activity given the first one,
+    * to satisfy the minimum lag.
+    * from_start: start time of first activity.
+    * from_duration: duration of first activity.
+    * to_duration: duration of second activity.
+    */
+    pub fn earliest_start(&self, from_start: i32, from_duration: u32, to_duration: u32) -> i32 {
+        let from_time = if self.kind.is_from_start() { from_start } else { from_start + from_duration as i32 };
+        let to_offset = if self.kind.is_to_start() { 0 } else { to_duration as i32 };
+        from_time + self.min_lag - to_offset
+    }
+
+    /**
+    * Return the time units that a schedule misses the lags of relation, 0 if it's satisfied.
+    * from_start: start time of first activity.
+    * from_duration: duration of first activity.
+    * to_start: start time of second activity.
+    * to_duration: duration of second activity.
+    */
+    pub fn excess(&self, from_start: i32, from_duration: u32, to_start: i32, to_duration: u32) -> u32 {
+        let from_time = if self.kind.is_from_start() { from_start } else { from_start + from_duration as i32 };
+        let to_time = if self.kind.is_to_start() { to_start } else { to_start + to_duration as i32 };
+        let lag = to_time - from_time;
+        if lag < self.min_lag {
+            return (self.min_lag - lag) as u32;
+        }
+        match self.max_lag {
+            Some(max_lag) if lag > max_lag => (lag - max_lag) as u32,
+            _ => 0,
+        }
+    }
+}
+
+/**
+* Read a file of generalized precedence relations and add them to a project.
+* filename: path to the relations.
+* project: project of the relations.
+*/
+pub fn read_relations(project: &mut Project, filename: &str) -> Result<(), String> {
+    let contents = fs::read_to_string(filename)
+            .map_err(|e| format!("No se pudo leer el archivo {}: {}", filename, e))?;
+    parse_relations(project, &contents)
+}
+
+/**
+* Add the relations in the contents of a file to a project.
+* Each line (except comments starting with '#') is a relation
+* '<from> <to> <type> <min_lag> [<max_lag>]', where activities are given by id or name
+* and type is SS, SF, FS or FF.
+* project: project of the relations.
+* contents: text of the relations.
+*/
+pub fn parse_relations(project: &mut Project, contents: &str) -> Result<(), String> {
+    for line in contents.lines().map(|x| x.trim()).filter(|x| !x.is_empty() && !x.starts_with('#')) {
+        let tokens : Vec<&str> = line.split_whitespace().collect();
+        if tokens.len() != 4 && tokens.len() != 5 {
+            return Err(format!("Relacion incompleta: '{}'", line));
+        }
+        let activity = |token: &str| project.activities.iter()
+                                                       .find(|x| x.id.to_string() == token || x.name == token)
+                                                       .map(|x| x.id)
+                                                       .ok_or_else(|| format!("Actividad {} no encontrada en '{}'", token, line));
+        let from = activity(tokens[0])?;
+        let to = activity(tokens[1])?;
+        let kind = RelationType::from_str(tokens[2]).ok_or_else(|| format!("Tipo de relacion invalido '{}' en '{}'", tokens[2], line))?;
+        let min_lag = tokens[3].parse::<i32>().map_err(|_| format!("Valor invalido '{}' en '{}'", tokens[3], line))?;
+        let max_lag = match tokens.get(4) {
+            Some(t) => Some(t.parse::<i32>().map_err(|_| format!("Valor invalido '{}' en '{}'", t, line))?),
+            None => None,
+        };
+        if from == to || max_lag.is_some_and(|x| x < min_lag) {
+            return Err(format!("Relacion invalida '{}'", line));
+        }
+        project.relations.push(Relation::new(from, to, kind, min_lag, max_lag));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+ mod tests {
+     use crate::prcpsp::relation as relation;
+     use crate::prcpsp::relation::Relation as Relation;
+     use crate::prcpsp::relation::RelationType as RelationType;
+     use crate::prcpsp::psplib as psplib;
+     use crate::prcpsp::sa_state::SaState as SaState;
+     use crate::prcpsp::validator as validator;
+     use crate::prcpsp::validator::Violation as Violation;
+     use crate::metaheuristics::state::State as State;
+
+     #[test]
+     fn test_relation() {
+         let relation = Relation::new(1, 2, RelationType::FinishFinish, 2, Some(4));
+         assert_eq!(relation.earliest_start(0, 3, 2), 3);
+         assert_eq!(relation.excess(0, 3, 3, 2), 0);
+         assert_eq!(relation.excess(0, 3, 6, 2), 1);
+         assert_eq!(relation.excess(0, 3, 1, 2), 2);
+         let relation = Relation::new(1, 2, RelationType::StartStart, -1, None);
+         assert_eq!(relation.earliest_start(4, 3, 2), 3);
+     }
+
+     #[test]
+     fn test_relations_planning() {
+         let mut project = psplib::read_sm("examples/psplib-9.sm").unwrap();
+         // 8 starts 2 units after 4 starts, and 3 finishes at most 3 units after 2 finishes
+         relation::parse_relations(&mut project, "# Curado\n4 8 SS 2\n2 3 FF 0 3\n").unwrap();
+         assert_eq!(project.relations.len(), 2);
+
+         let state = SaState::new(project.clone(), 11);
+         let start = |id: u32| state.times[state.planning.iter().position(|x| *x == id).unwrap()];
+         assert!(start(8) >= start(4) + 2);
+         assert!(validator::validate(&state.project, &state.planning, &state.times).is_empty());
+         assert_eq!(state.get_penalty(), 0);
+         assert!(state.missed_relations.is_empty());
+
+         let state = SaState::new(project.pre_emptive_project(2), 11);
+         assert!(validator::validate(&state.project, &state.planning, &state.times).is_empty());
+
+         // 3 is planned before 7 finishes
+         relation::parse_relations(&mut project, "7 3 FS 0\n").unwrap();
+         let state = SaState::new(project, 11);
+         let violations = validator::validate(&state.project, &state.planning, &state.times);
+         assert!(violations.iter().any(|x| matches!(x, Violation::Relation(7, 3, RelationType::FinishStart, _))));
+         assert!(state.get_cost() > (0, state.get_makespan() as f64));
+         assert!(state.missed_relations.iter().any(|(r, excess)| (r.from, r.to) == (7, 3) && *excess > 0));
+
+         let mut project = psplib::read_sm("examples/psplib-9.sm").unwrap();
+         assert!(relation::parse_relations(&mut project, "2 3 XX 0\n").is_err());
+         assert!(relation::parse_relations(&mut project, "2 30 SS 0\n").is_err());
+         assert!(relation::parse_relations(&mut project, "2 3 SS 4 2\n").is_err());
+     }
+ }
@@ -1,16 +1,43 @@
 use core::cmp::Ordering;
 
-/* Represents a renewable resource */
+/* Represents a renewable resource,
+   with periods (start, end, capacity) where its capacity changes
+   and the cost of each unit of capacity. */
 #[derive(Debug,Clone)]
 pub struct RnResource {
     pub id: u32,
     pub name: String,
     pub capacity: u32,
+    pub periods: Vec<(i32, i32, u32)>,
+    pub cost: u32,
 }
 
 impl RnResource {
     pub fn new(id: u32, name: String, capacity: u32) -> RnResource {
-        RnResource { id, name, capacity }
+        RnResource { id, name, capacity, periods: vec![], cost: 0 }
+    }
+
+    /**
+    * Change the capacity of resource in a period of time.
+    * Later periods override the ones added before.
+    * start: first time of period.
+    * end: time where period finishes (not included).
+    * capacity: capacity in period.
+    */
+    pub fn add_period(&mut self, start: i32, end: i32, capacity: u32) {
+        self.periods.push((start, end, capacity));
+    }
+
+    /**
+    * Return the capacity of resource at a time, from the last period
+    * containing it or the constant capacity otherwise.
+    * time: time to check.
+    */
+    pub fn capacity_at(&self, time: i32) -> u32 {
+        self.periods.iter()
+                    .rev()
+                    .find(|(start, end, _)| *start <= time && time < *end)
+                    .map_or(self.capacity, |(_, _, capacity)| *capacity)
     }
 }
 
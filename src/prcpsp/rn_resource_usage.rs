@@ -2,16 +2,19 @@ use crate::prcpsp::rn_resource::RnResource as RnResource;
 
 /**
 *   Represents the units that actual activity is using
-*   for a renewable resource.
+*   for a renewable resource. If the resource is a skill
+*   of workers, usage is the number of workers and level
+*   the minimum level they need in the skill.
 */
 #[derive(Debug,Clone)]
 pub struct RnResourceUsage {
     pub resource: RnResource,
     pub usage: u32,
+    pub level: u32,
 }
 
 impl RnResourceUsage {
     pub fn new(resource: RnResource, usage: u32) -> RnResourceUsage {
-        RnResourceUsage { resource, usage }
+        RnResourceUsage { resource, usage, level: 0 }
     }
 }
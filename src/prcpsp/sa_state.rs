@@ -1,8 +1,46 @@
 use rand::{Rng, SeedableRng, StdRng};
 use crate::prcpsp::project::Project as Project;
 use crate::prcpsp::activity::Activity as Activity;
+use crate::prcpsp::relation::Relation as Relation;
+use crate::prcpsp::objective::Objective as Objective;
+use crate::prcpsp::decoder::Decoder as Decoder;
+use crate::prcpsp::justification as justification;
 use crate::metaheuristics::state::State as State;
 
+/**
+* Movements to get a neighbor, encoded as usize for State trait:
+* swaps are their index in planning (0 is no movement),
+* mode changes are (mode + 1) * len + index of the activity in planning and
+* capacity changes are counted down from usize::MAX as capacity * resources + index of the resource.
+*/
+#[derive(Debug,Clone,Copy,PartialEq)]
+enum Movement {
+    Swap(usize),
+    Mode(usize, usize),
+    Capacity(usize, u32),
+}
+
+impl Movement {
+    fn encode(&self, len: usize, resources: usize) -> usize {
+        match self {
+            Movement::Swap(index) => *index,
+            Movement::Mode(index, mode) => (mode + 1) * len + index,
+            Movement::Capacity(resource, capacity) => usize::MAX - (*capacity as usize * resources + resource),
+        }
+    }
+
+    fn decode(movement: usize, len: usize, resources: usize) -> Movement {
+        if movement < len {
+            Movement::Swap(movement)
+        } else if movement > usize::MAX / 2 {
+            let value = usize::MAX - movement;
+            Movement::Capacity(value % resources, (value / resources) as u32)
+        } else {
+            Movement::Mode(movement % len, movement / len - 1)
+        }
+    }
+}
+
 /**
 * State representation for Simulated annealing.
 */
@@ -11,7 +49,14 @@ pub struct SaState {
     pub project: Project,
     rng: StdRng,
     pub planning: Vec<u32>,
-    pub times: Vec<i32>
+    pub times: Vec<i32>,
+    pub objective: Objective,
+    pub decoder: Decoder,
+    pub justification: bool,
+    /* Relations whose lags the decoder couldn't meet in the order of planning, with the time units missed. */
+    pub missed_relations: Vec<(Relation, u32)>,
+    /* Resources whose capacity is chosen by the search with their range of capacities, only for capacity cost. */
+    adjustable: Vec<(usize, u32, u32)>,
 }
 
 impl SaState {
@@ -24,6 +69,42 @@ impl SaState {
         SaState::get_state(project, seed)
     }
 
+    /**
+    * Create a state from an activity list, as one saved from a previous run.
+    * The list should have every activity of project after its predecessors.
+    * project: project to planning.
+    * planning: activity list.
+    * seed: seed for pseudo-random numbers.
+    */
+    pub fn from_planning(mut project: Project, planning: Vec<u32>, seed: u64) -> Result<SaState, String> {
+        if planning.len() != project.activities.len() {
+            return Err(format!("La lista tiene {} actividades y el proyecto {}", planning.len(), project.activities.len()));
+        }
+        if planning.first() != project.activities.first().map(|x| &x.id) ||
+           planning.last() != project.activities.last().map(|x| &x.id) {
+            return Err(String::from("Actividades ficticias de inicio y fin no encontradas"));
+        }
+        for (i, id) in planning.iter().enumerate() {
+            let activity = match project.activities.iter().find(|x| x.id == *id) {
+                Some(activity) => activity,
+                None => return Err(format!("La actividad {} no esta en el proyecto", id)),
+            };
+            if planning[..i].contains(id) {
+                return Err(format!("La actividad {} esta repetida", id));
+            }
+            if let Some(p) = activity.predecessors.iter().find(|x| !planning[..i].contains(x)) {
+                return Err(format!("La actividad {} esta antes de su predecesor {}", id, p));
+            }
+        }
+        for activity in &mut project.activities {
+            activity.start_time = -1;
+        }
+        let times = vec![0; planning.len()];
+        let mut state = SaState{ project, rng: SeedableRng::seed_from_u64(seed), planning, times, objective: Objective::Makespan, decoder: Decoder::Serial, justification: false, missed_relations: vec![], adjustable: vec![] };
+        state.get_planning();
+        Ok(state)
+    }
+
     /**
     * From a started project, create a new state.
     * project: project started.
@@ -42,7 +123,8 @@ impl SaState {
             planning.push(activity.id);
             times.push(activity.start_time)
         }
-        return SaState{ project, rng: SeedableRng::seed_from_u64(seed), planning: planning, times: times};
+        let missed_relations = project.missed_relations();
+        SaState{ project, rng: SeedableRng::seed_from_u64(seed), planning, times, objective: Objective::Makespan, decoder: Decoder::Serial, justification: false, missed_relations, adjustable: vec![] }
     }
 
     /**
@@ -64,27 +146,111 @@ impl SaState {
                 queue.push(current);
                 continue;
             }
-            time = project.get_time_planning(current.clone(), time);
-            while project.resource_conflict(current.clone(), time) {
-                time += 1;
-            }
-            project.set_time(current.clone(), time);
+            let start = project.plan_activity(&current, time);
+            time = time.max(start);
             planning.push(current.id);
-            times.push(time);
+            times.push(start);
             let successors : Vec<Activity> = project.activities.clone()
                                                                .into_iter()
                                                                .filter(|x| current.successors.contains(&x.id) &&
                                                                            !queue.contains(&x) && x.start_time == -1 ).collect();
             successors.iter().for_each(|x| queue.push(x.clone()));
         }
-        return SaState{ project, rng: SeedableRng::seed_from_u64(seed), planning: planning, times: times};
+        let missed_relations = project.missed_relations();
+        SaState{ project, rng: SeedableRng::seed_from_u64(seed), planning, times, objective: Objective::Makespan, decoder: Decoder::Serial, justification: false, missed_relations, adjustable: vec![] }
+    }
+
+    /**
+    * Select the objective minimized for the state and its neighbors, makespan by default.
+    * objective: objective to minimize.
+    */
+    pub fn set_objective(&mut self, objective: Objective) {
+        self.objective = objective;
+        self.adjustable = if objective == Objective::CapacityCost { self.project.adjustable_resources() } else { vec![] };
+    }
+
+    /**
+    * Select the decoder of the state and its neighbors, serial by default,
+    * and decode the activity list again with it.
+    * decoder: schedule generation scheme.
+    */
+    pub fn set_decoder(&mut self, decoder: Decoder) {
+        self.decoder = decoder;
+        self.get_planning();
+    }
+
+    /**
+    * Select if every activity list decoded for the state and its neighbors
+    * is improved by double justification, and decode the activity list again.
+    * justification: true to justify every schedule.
+    */
+    pub fn set_justification(&mut self, justification: bool) {
+        self.justification = justification;
+        self.get_planning();
     }
 
     /**
     * According to planning vector, for each activity,
-    * get its start_time and save it in times vector.
+    * get its start_time and save it in times vector,
+    * with the decoder of the state and double justification if it's selected,
+    * and record the relations whose lags can't be met.
     */
     pub fn get_planning(&mut self) {
+        self.decode();
+        if self.justification {
+            self.justify();
+        }
+        self.missed_relations = self.project.missed_relations();
+    }
+
+    /**
+    * Improve the schedule by double justification: shift every activity as late as possible
+    * without changing the makespan, then decode again the activity list in the order of those
+    * start times, so activities start as early as possible. The new activity list and schedule
+    * are kept if their cost isn't worse. Return true if the activity list changed.
+    */
+    pub fn justify(&mut self) -> bool {
+        let right = justification::right_justify(&self.project, self.get_makespan() as i32);
+        let planning = justification::start_order(&right, &self.planning);
+        if planning == self.planning {
+            return false;
+        }
+        let mut candidate = self.clone();
+        candidate.planning = planning;
+        candidate.decode();
+        if candidate.get_cost() > self.get_cost() {
+            return false;
+        }
+        self.planning = candidate.planning;
+        self.project = candidate.project;
+        self.times = candidate.times;
+        true
+    }
+
+    /**
+    * Decode the activity list with the decoder of the state.
+    */
+    fn decode(&mut self) {
+        match self.decoder {
+            Decoder::Serial => self.serial_planning(),
+            Decoder::Parallel => self.parallel_planning(),
+            Decoder::Hybrid => {
+                let mut parallel = self.clone();
+                parallel.parallel_planning();
+                self.serial_planning();
+                if parallel.get_cost() < self.get_cost() {
+                    self.project = parallel.project;
+                    self.times = parallel.times;
+                }
+            }
+        }
+    }
+
+    /**
+    * Serial decoder: plan each activity in planning order at the earliest
+    * feasible time from the start time of the previous one.
+    */
+    fn serial_planning(&mut self) {
         let mut time = 0;
         let other = self.project.clone();
         for activity in &mut self.project.activities {
@@ -92,12 +258,42 @@ impl SaState {
         }
         for (i,id) in self.planning.iter().enumerate() {
             let current = other.activities.iter().find(|x|x.id == *id).unwrap();
-            time = self.project.get_time_planning(current.clone(), time);
-            while self.project.resource_conflict(current.clone(), time) {
+            // A piece started earlier to meet its maximum gap doesn't move back the next ones
+            let start = self.project.plan_activity(current, time);
+            time = time.max(start);
+            self.times[i] = start;
+        }
+    }
+
+    /**
+    * Parallel decoder: from time 0, plan at each time the activities in planning order whose
+    * predecessors have finished and that fit in the resources left, then go to the next time.
+    * No activity waits while it could start, so schedules are non-delay.
+    */
+    fn parallel_planning(&mut self) {
+        let other = self.project.clone();
+        for activity in &mut self.project.activities {
+            activity.start_time = -1;
+        }
+        let mut pending : Vec<usize> = (0..self.planning.len()).collect();
+        let mut time = 0;
+        while !pending.is_empty() {
+            let mut planned : Vec<usize> = vec![];
+            for i in &pending {
+                let current = other.activities.iter().find(|x| x.id == self.planning[*i]).unwrap();
+                if !self.project.predecessors_planned(current.clone()) || self.project.get_time_planning(current.clone(), time) > time {
+                    continue;
+                }
+                if self.project.plan_activity_at(current, time) {
+                    self.times[*i] = time;
+                    planned.push(*i);
+                }
+            }
+            // Activities of duration 0 can let others start at the same time
+            if planned.is_empty() {
                 time += 1;
             }
-            self.project.set_time(current.clone(), time);
-            self.times[i] = time;
+            pending.retain(|x| !planned.contains(x));
         }
     }
 
@@ -109,15 +305,44 @@ impl SaState {
         return *last as u32;
     }
 
+    /**
+    * Return the penalty for infeasibilities that the decoder doesn't avoid,
+    * as non-renewable resources consumed over their capacity by the selected modes,
+    * lags of relations that can't be met in the order of planning, activities
+    * finishing after their deadlines or subactivities missing their segment constraints.
+    * Each unit over capacity, lag, deadline or segment costs more than the objective
+    * can vary for the longest possible makespan. The penalty is compared before the
+    * objective in the cost, so any feasible state is better than an infeasible one,
+    * and simulated annealing rarely accepts an infeasible neighbor.
+    */
+    pub fn get_penalty(&self) -> u32 {
+        let excess = self.project.nr_excess() + self.project.lag_excess() + self.project.deadline_excess() + self.project.segment_excess();
+        if excess == 0 {
+            return 0;
+        }
+        let horizon : u32 = self.project.activities.iter()
+                                                   .map(|x| x.modes.iter().map(|m| m.duration).fold(x.duration, u32::max))
+                                                   .sum();
+        let release = self.project.activities.iter().map(|x| x.release.max(0) as u32).max().unwrap_or(0);
+        excess.saturating_mul(self.objective.upper_bound(&self.project, horizon + release).saturating_add(1))
+    }
+
     /**
     * Get a neighbor cost and index from current state.
     * Create a neighbor swapping three activities around a position randomly taked in planning vector.
     *
-    * Return a pair (cost, index, activities) where cost is the makespan of neighbor
+    * Return a pair (cost, index, activities) where cost is the penalty and objective of neighbor
     * and index is the position in planning vector where swapping create the neighbor,
     * activities, the activities swapped.
     */
-    fn get_neighbor(&mut self) -> (u32, usize, Vec<u32>) {
+    fn get_neighbor(&mut self) -> ((u32, f64), usize, Vec<u32>) {
+        if !self.adjustable.is_empty() && self.rng.gen::<bool>() {
+            return self.get_capacity_neighbor();
+        }
+        let multi_mode = self.project.multi_mode_activities();
+        if !multi_mode.is_empty() && self.rng.gen::<bool>() {
+            return self.get_mode_neighbor(&multi_mode);
+        }
         for _ in 0..10 {
             let i= self.rng.gen_range(2, self.project.activities.len()-2) as usize;
             let id_choosen_before = self.planning[i-1];
@@ -130,12 +355,12 @@ impl SaState {
                !activity.is_predecessor(before_activity.clone()) &&
                !before_activity.is_predecessor(next_activity.clone()) &&
                !next_activity.is_predecessor(before_activity.clone()) {
-                   let mut neighbor = SaState { project: self.project.clone(), planning: self.planning.clone(), times: self.times.clone(), rng: self.rng.clone() };
+                   let mut neighbor = SaState { project: self.project.clone(), planning: self.planning.clone(), times: self.times.clone(), rng: self.rng.clone(), objective: self.objective, decoder: self.decoder, justification: self.justification, missed_relations: vec![], adjustable: self.adjustable.clone() };
                    neighbor.planning[i-1] = next_activity.id;
                    neighbor.planning[i] = before_activity.id;
                    neighbor.planning[i+1] = activity.id;
                    neighbor.get_planning();
-                   let neighbor_cost = neighbor.get_makespan();
+                   let neighbor_cost = neighbor.get_cost();
                    let activities = vec![before_activity.id, activity.id, next_activity.id];
                    return (neighbor_cost, i, activities);
             }
@@ -143,7 +368,51 @@ impl SaState {
         if self.can_generate_neighbor() {
             return self.get_neighbor();
         }
-        return (0,0,vec![]);
+        if !multi_mode.is_empty() {
+            return self.get_mode_neighbor(&multi_mode);
+        }
+        ((0, 0.0),0,vec![])
+    }
+
+    /**
+    * Get a neighbor changing the mode of an activity randomly taked.
+    * Return a pair (cost, movement, activities) as get_neighbor, where activities
+    * is the id of the activity in the original project.
+    * multi_mode: activities with more than one mode.
+    */
+    fn get_mode_neighbor(&mut self, multi_mode: &[u32]) -> ((u32, f64), usize, Vec<u32>) {
+        let parent = multi_mode[self.rng.gen_range(0, multi_mode.len())];
+        let activity = self.project.activities.iter().find(|x| x.get_parent() == parent).unwrap();
+        let mut mode = self.rng.gen_range(0, activity.modes.len() - 1);
+        if mode >= activity.mode {
+            mode += 1;
+        }
+        let index = self.planning.iter().position(|x| *x == activity.id).unwrap();
+        let mut neighbor = self.clone();
+        neighbor.project.set_mode(parent, mode);
+        neighbor.get_planning();
+        let movement = Movement::Mode(index, mode).encode(self.planning.len(), self.project.resources.len());
+        (neighbor.get_cost(), movement, vec![parent])
+    }
+
+    /**
+    * Get a neighbor changing the capacity of a resource randomly taked to another
+    * capacity between its bounds. Return a pair (cost, movement, activities) as get_neighbor,
+    * where activities is u32::MAX, which isn't the id of any activity, and the id of the
+    * resource, so the resource is tabu apart from the activities of swaps and mode changes.
+    */
+    fn get_capacity_neighbor(&mut self) -> ((u32, f64), usize, Vec<u32>) {
+        let (r, lowest, highest) = self.adjustable[self.rng.gen_range(0, self.adjustable.len())];
+        let resource = &self.project.resources[r];
+        let mut capacity = self.rng.gen_range(lowest, highest);
+        if capacity >= resource.capacity {
+            capacity += 1;
+        }
+        let id = resource.id;
+        let mut neighbor = self.clone();
+        neighbor.change_capacity(r, capacity);
+        let movement = Movement::Capacity(r, capacity).encode(self.planning.len(), self.project.resources.len());
+        (neighbor.get_cost(), movement, vec![u32::MAX, id])
     }
 
     /**
@@ -186,6 +455,37 @@ impl SaState {
         self.get_planning();
     }
 
+    /**
+    * Update the mode of an activity and its subactivities.
+    * index: position in planning of an activity (or subactivity).
+    * mode: index of the new mode.
+    */
+    fn change_mode(&mut self, index: usize, mode: usize) {
+        let id = self.planning[index];
+        let parent = self.project.activities.iter().find(|x| x.id == id).unwrap().get_parent();
+        self.project.set_mode(parent, mode);
+        self.get_planning();
+    }
+
+    /**
+    * Update the capacity of a resource.
+    * resource: index of the resource.
+    * capacity: new capacity.
+    */
+    fn change_capacity(&mut self, resource: usize, capacity: u32) {
+        self.project.resources[resource].capacity = capacity;
+        self.get_planning();
+    }
+
+    /**
+    * Return the mode of each activity in planning.
+    */
+    pub fn get_modes(&self) -> Vec<u32> {
+        self.planning.iter()
+                     .map(|id| self.project.activities.iter().find(|x| x.id == *id).unwrap().mode as u32)
+                     .collect()
+    }
+
     pub fn get_string(&self) -> String {
         let mut str = String::from("   [");
         for (i,p) in self.planning.iter().enumerate() {
@@ -428,6 +728,10 @@ impl SaState {
             }
 
             let activity = self.project.activities.iter().find(|x|x.id == id).unwrap();
+            // Pieces without duration in the current mode aren't drawn
+            if activity.duration == 0 {
+                continue;
+            }
 
             let width_rectangle = activity.duration * 100;
             let height_rectangle = activity.get_demand() * 100;
@@ -584,17 +888,23 @@ impl SaState {
 }
 
 impl State for SaState {
+    /* Penalty and then objective, compared lexicographically. */
+    type Cost = (u32, f64);
 
-    fn get_neighbor(&mut self) -> (u32, usize, Vec<u32>) {
+    fn get_neighbor(&mut self) -> ((u32, f64), usize, Vec<u32>) {
         return self.get_neighbor();
     }
 
     fn set_neighbor(&mut self, movement: usize) {
-        self.change_planning(movement);
+        match Movement::decode(movement, self.planning.len(), self.project.resources.len()) {
+            Movement::Swap(index) => self.change_planning(index),
+            Movement::Mode(index, mode) => self.change_mode(index, mode),
+            Movement::Capacity(resource, capacity) => self.change_capacity(resource, capacity),
+        }
     }
 
-    fn get_cost(&self) -> u32 {
-        return self.get_makespan();
+    fn get_cost(&self) -> (u32, f64) {
+        (self.get_penalty(), self.objective.evaluate(&self.project, self.get_makespan()))
     }
 
     fn to_string(&self) -> String {
@@ -621,6 +931,8 @@ impl State for SaState {
      use crate::prcpsp::rn_resource::RnResource as RnResource;
      use crate::prcpsp::rn_resource_usage::RnResourceUsage as RnResourceUsage;
      use crate::prcpsp::sa_state::SaState as SaState;
+     use crate::prcpsp::mode::Mode as Mode;
+     use crate::metaheuristics::state::State as State;
 
      fn initial() -> Project {
          let mut initial = Activity::new(1,-1,String::from("1"),vec![],vec![],vec![],0,-1);
@@ -821,7 +1133,7 @@ impl State for SaState {
         state.planning[i+1] = current;
         state.get_planning();
         let expected = state.times.last().unwrap();
-        assert_eq!(cost_neighbor, *expected as u32);
+        assert_eq!(cost_neighbor, (0, *expected as f64));
         let activity = state.project.activities.iter().find(|x| x.id == current).unwrap();
         let before_activity = state.project.activities.iter().find(|x| x.id == before).unwrap();
         let next_activity = state.project.activities.iter().find(|x| x.id == next).unwrap();
@@ -858,6 +1170,33 @@ impl State for SaState {
         assert_eq!(expected, state.times);
      }
 
+    #[test]
+    fn test_mode_neighbor() {
+        let mut project = initial();
+        let resource = project.resources[0].clone();
+        project.activities[3].modes = vec![Mode::new(1, 4, vec![RnResourceUsage::new(resource.clone(), 2)], vec![]),
+                                           Mode::new(2, 2, vec![RnResourceUsage::new(resource, 4)], vec![])];
+        let project = project.pre_emptive_project(1);
+        let pieces : Vec<&Activity> = project.activities.iter().filter(|x| x.parent == 4).collect();
+        assert_eq!(pieces.len(), 2);
+        assert!(pieces.iter().all(|x| x.modes.len() == 2 && x.modes[1].duration == 1));
+
+        let mut state = SaState::new(project, 11);
+        let mut found = false;
+        for _ in 0..20 {
+            let (cost, movement, activities) = state.get_neighbor();
+            if activities == vec![4] {
+                state.set_neighbor(movement);
+                assert_eq!(state.get_cost(), cost);
+                let pieces : Vec<&Activity> = state.project.activities.iter().filter(|x| x.parent == 4).collect();
+                assert!(pieces.iter().all(|x| x.mode == 1 && x.duration == 1 && x.get_demand() == 4));
+                found = true;
+                break;
+            }
+        }
+        assert!(found);
+    }
+
     #[test]
     fn test_set_movement() {
         let project = initial();
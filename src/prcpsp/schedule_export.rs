@@ -0,0 +1,266 @@
+use crate::prcpsp::project::Project as Project;
+use crate::prcpsp::activity::Activity as Activity;
+use crate::prcpsp::sa_state::SaState as SaState;
+
+/* First day of the project in MS Project files, a Monday. */
+const START_DATE : (i64, i64, i64) = (2000, 1, 3);
+/* Working hours in a time unit (one day). */
+const HOURS_PER_DAY : u32 = 8;
+
+/**
+* Piece of an activity in a schedule.
+*/
+struct Piece<'a> {
+    activity: &'a Activity,
+    parent: u32,
+    start: i32,
+    finish: i32,
+}
+
+/**
+* Return the pieces of a schedule in the order of start time, without dummy activities.
+* A piece is an activity or subactivity, its parent is the activity of original project.
+*/
+fn pieces(state: &SaState) -> Vec<Piece<'_>> {
+    let first = state.project.activities.first().unwrap().id;
+    let last = state.project.activities.last().unwrap().id;
+    let mut pieces : Vec<Piece> = state.planning.iter()
+                                               .zip(state.times.iter())
+                                               .filter(|(id, _)| **id != first && **id != last)
+                                               .map(|(id, time)| {
+                                                   let activity = state.project.activities.iter().find(|x| x.id == *id).unwrap();
+                                                   Piece { activity, parent: activity.get_parent(), start: *time, finish: *time + activity.duration as i32 }
+                                               })
+                                               .collect();
+    pieces.sort_by_key(|x| (x.start, x.activity.id));
+    pieces
+}
+
+/**
+* Return the name of an activity of the original project.
+*/
+fn parent_name(original: &Project, parent: u32) -> String {
+    original.activities.iter().find(|x| x.id == parent).map_or(parent.to_string(), |x| x.name.clone())
+}
+
+/**
+* Return a CSV field, quoted if needed.
+*/
+fn csv_field(value: &str) -> String {
+    if value.contains(',') || value.contains('"') {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/**
+* Return the schedule of a state as CSV, with a row for each (sub)activity:
+* id, parent id, parent name, start, finish and usage of each resource.
+* original: project before splitting activities, for names of activities.
+* state: state to export.
+*/
+pub fn to_csv(original: &Project, state: &SaState) -> String {
+    let mut content = String::from("id,parent,name,start,finish");
+    for r in &state.project.resources {
+        content.push(',');
+        content.push_str(&csv_field(&r.name));
+    }
+    content.push('\n');
+    for piece in pieces(state) {
+        content.push_str(&format!("{},{},{},{},{}", piece.activity.id, piece.parent, csv_field(&parent_name(original, piece.parent)), piece.start, piece.finish));
+        for r in &state.project.resources {
+            let usage = piece.activity.supplies.iter().find(|x| x.resource == *r).map_or(0, |x| x.usage);
+            content.push_str(&format!(",{}", usage));
+        }
+        content.push('\n');
+    }
+    content
+}
+
+/**
+* Return the date of a working day after START_DATE as YYYY-MM-DD, skipping weekends.
+*/
+fn date(days: i32) -> String {
+    let (y, m, d) = START_DATE;
+    // START_DATE is a Monday, so each 5 working days are a week
+    let days = days / 5 * 7 + days % 5;
+    // Days from 0000-03-01 to START_DATE
+    let y = if m <= 2 { y - 1 } else { y };
+    let era = y / 400;
+    let yoe = y - era * 400;
+    let doy = (153 * (if m > 2 { m - 3 } else { m + 9 }) + 2) / 5 + d - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let z = era * 146_097 + doe + days as i64;
+    // Back to a civil date
+    let era = z / 146_097;
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = doy - (153 * mp + 2) / 5 + 1;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 };
+    let y = yoe + era * 400 + if m <= 2 { 1 } else { 0 };
+    format!("{:04}-{:02}-{:02}", y, m, d)
+}
+
+/**
+* Return the date and time where a time unit starts.
+*/
+fn start_date(time: i32) -> String {
+    format!("{}T08:00:00", date(time))
+}
+
+/**
+* Return the date and time where a time unit before finish ends.
+*/
+fn finish_date(finish: i32, start: i32) -> String {
+    if finish == start {
+        return start_date(start);
+    }
+    format!("{}T{:02}:00:00", date(finish - 1), 8 + HOURS_PER_DAY + 1)
+}
+
+/**
+* Return a duration of some time units in XML format.
+*/
+fn duration(units: i32) -> String {
+    format!("PT{}H0M0S", units * HOURS_PER_DAY as i32)
+}
+
+/**
+* Return a text escaped for XML.
+*/
+fn escape(value: &str) -> String {
+    value.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+/**
+* Return the schedule of a state as MS Project XML. Each activity of the original project is a task,
+* manually scheduled with the start and finish of its pieces, and the pieces of a pre-empted activity
+* are segments of work in the timephased data of its assignments, so the task appears split.
+* Each time unit is a working day starting at START_DATE.
+* original: project before splitting activities.
+* state: state to export.
+* name: name of the project.
+*/
+pub fn to_msproject(original: &Project, state: &SaState, name: &str) -> String {
+    let pieces = pieces(state);
+    let first = original.activities.first().unwrap().id;
+    let last = original.activities.last().unwrap().id;
+    let tasks : Vec<&Activity> = original.activities.iter().filter(|x| x.id != first && x.id != last).collect();
+
+    let mut content = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n");
+    content.push_str("<Project xmlns=\"http://schemas.microsoft.com/project\">\n");
+    content.push_str(&format!("  <Name>{}</Name>\n", escape(name)));
+    content.push_str(&format!("  <StartDate>{}</StartDate>\n", start_date(0)));
+    content.push_str(&format!("  <MinutesPerDay>{}</MinutesPerDay>\n", HOURS_PER_DAY * 60));
+    content.push_str("  <Tasks>\n");
+    let mut segments : Vec<(u32, Vec<(i32, i32)>)> = vec![];
+    for (i, task) in tasks.iter().enumerate() {
+        let mut parts : Vec<(i32, i32)> = pieces.iter().filter(|x| x.parent == task.id).map(|x| (x.start, x.finish)).collect();
+        parts.sort();
+        // Contiguous pieces are one segment
+        let mut merged : Vec<(i32, i32)> = vec![];
+        for (start, finish) in parts {
+            match merged.last_mut() {
+                Some(last) if last.1 == start => last.1 = finish,
+                _ => merged.push((start, finish)),
+            }
+        }
+        let start = merged.first().map_or(0, |x| x.0);
+        let finish = merged.last().map_or(0, |x| x.1);
+        let work : i32 = merged.iter().map(|(s, f)| f - s).sum();
+        content.push_str("    <Task>\n");
+        content.push_str(&format!("      <UID>{}</UID>\n      <ID>{}</ID>\n", task.id, i + 1));
+        content.push_str(&format!("      <Name>{}</Name>\n", escape(&task.name)));
+        content.push_str("      <Manual>1</Manual>\n");
+        content.push_str(&format!("      <Start>{}</Start>\n      <Finish>{}</Finish>\n", start_date(start), finish_date(finish, start)));
+        content.push_str(&format!("      <Duration>{}</Duration>\n      <DurationFormat>7</DurationFormat>\n", duration(work)));
+        for pred in task.predecessors.iter().filter(|x| **x != first) {
+            content.push_str(&format!("      <PredecessorLink>\n        <PredecessorUID>{}</PredecessorUID>\n        <Type>1</Type>\n      </PredecessorLink>\n", pred));
+        }
+        content.push_str("    </Task>\n");
+        segments.push((task.id, merged));
+    }
+    content.push_str("  </Tasks>\n");
+
+    content.push_str("  <Resources>\n");
+    for r in &original.resources {
+        content.push_str(&format!("    <Resource>\n      <UID>{}</UID>\n      <ID>{}</ID>\n      <Name>{}</Name>\n      <MaxUnits>{}</MaxUnits>\n    </Resource>\n", r.id, r.id, escape(&r.name), r.capacity));
+    }
+    content.push_str("  </Resources>\n");
+
+    content.push_str("  <Assignments>\n");
+    let mut uid = 1;
+    for (task, merged) in &segments {
+        let activity = original.activities.iter().find(|x| x.id == *task).unwrap();
+        // Tasks without resources use the unassigned resource of MS Project
+        let mut usages : Vec<(i64, u32)> = activity.supplies.iter().map(|x| (x.resource.id as i64, x.usage)).collect();
+        if usages.is_empty() {
+            usages.push((-65535, 0));
+        }
+        for (resource, units) in usages {
+            let start = merged.first().map_or(0, |x| x.0);
+            let finish = merged.last().map_or(0, |x| x.1);
+            content.push_str("    <Assignment>\n");
+            content.push_str(&format!("      <UID>{}</UID>\n      <TaskUID>{}</TaskUID>\n      <ResourceUID>{}</ResourceUID>\n      <Units>{}</Units>\n", uid, task, resource, units));
+            content.push_str(&format!("      <Start>{}</Start>\n      <Finish>{}</Finish>\n", start_date(start), finish_date(finish, start)));
+            let mut time = start;
+            for (s, f) in merged {
+                if *s > time {
+                    content.push_str(&timephased(uid, time, *s, 0));
+                }
+                content.push_str(&timephased(uid, *s, *f, f - s));
+                time = *f;
+            }
+            content.push_str("    </Assignment>\n");
+            uid += 1;
+        }
+    }
+    content.push_str("  </Assignments>\n");
+    content.push_str("</Project>\n");
+    content
+}
+
+/**
+* Return a segment of work of an assignment, without work for interruptions.
+*/
+fn timephased(uid: u32, start: i32, finish: i32, work: i32) -> String {
+    format!("      <TimephasedData>\n        <Type>1</Type>\n        <UID>{}</UID>\n        <Start>{}</Start>\n        <Finish>{}</Finish>\n        <Unit>2</Unit>\n        <Value>{}</Value>\n      </TimephasedData>\n",
+            uid, start_date(start), finish_date(finish, start), duration(work))
+}
+
+#[cfg(test)]
+ mod tests {
+     use crate::prcpsp::schedule_export as schedule_export;
+     use crate::prcpsp::csv_project as csv_project;
+     use crate::prcpsp::sa_state::SaState as SaState;
+
+     #[test]
+     fn test_export() {
+         let project = csv_project::read_csv("examples/project.csv", "examples/project-resources.csv").unwrap();
+         let state = SaState::new(project.pre_emptive_project(1), 11);
+         let csv = schedule_export::to_csv(&project, &state);
+         let lines : Vec<&str> = csv.lines().collect();
+         assert_eq!(lines[0], "id,parent,name,start,finish,crew");
+         assert_eq!(lines.len(), state.planning.len() - 1);
+         assert!(lines.iter().any(|x| x.starts_with("2,2,Excavation,0,1,1")));
+         assert!(csv.contains(",\"Electric, plumbing\","));
+
+         let xml = schedule_export::to_msproject(&project, &state, "example");
+         assert_eq!(xml.matches("<Task>").count(), 7);
+         assert!(xml.contains("<Name>Electric, plumbing</Name>"));
+         assert!(xml.contains("<Start>2000-01-03T08:00:00</Start>"));
+         assert_eq!(xml.matches("<Assignment>").count(), 7);
+     }
+
+     #[test]
+     fn test_date() {
+         assert_eq!(schedule_export::date(0), "2000-01-03");
+         assert_eq!(schedule_export::date(4), "2000-01-07");
+         assert_eq!(schedule_export::date(5), "2000-01-10");
+         assert_eq!(schedule_export::date(29), "2000-02-11");
+         assert_eq!(schedule_export::date(366), "2001-05-29");
+     }
+ }
@@ -0,0 +1,273 @@
+use std::fs;
+
+use crate::prcpsp::project::Project as Project;
+use crate::prcpsp::sa_state::SaState as SaState;
+use crate::prcpsp::objective::Objective as Objective;
+use crate::prcpsp::decoder::Decoder as Decoder;
+
+/**
+* Solution of a run, which can be written and loaded back
+* as initial state for another run.
+*
+* File format (lines starting with '#' are comments):
+*
+*   instance <name>
+*   interruptions <m>
+*   makespan <cost>
+*   planning <id_1> .. <id_n>
+*   times <t_1> .. <t_n>
+*   modes <mode_1> .. <mode_n>
+*   workers <w_1> .. <w_n>
+*   capacities <c_1> .. <c_k>
+*   decoder <name>
+*
+* The modes line is optional, only written for multi-mode projects,
+* with the mode index of each activity in planning. The workers line is
+* optional too, only written for projects with workers, with the ids of the
+* workers assigned to each activity in planning separated by ',' or '-' if none.
+* The capacities line is written when the search chooses the capacities of the
+* resources, with the capacity of each resource in the order of the project.
+* The decoder line has the decoder that turned the activity list into the
+* times, and is missing when the times don't come from a decoder.
+*/
+#[derive(Debug,Clone)]
+pub struct Solution {
+    pub instance: String,
+    pub interruptions: u32,
+    pub makespan: u32,
+    pub planning: Vec<u32>,
+    pub times: Vec<i32>,
+    pub modes: Vec<u32>,
+    pub workers: Vec<Vec<u32>>,
+    pub capacities: Vec<u32>,
+    pub decoder: Option<Decoder>,
+}
+
+impl Solution {
+
+    pub fn new(instance: String, interruptions: u32, makespan: u32, planning: Vec<u32>, times: Vec<i32>) -> Solution {
+        Solution { instance, interruptions, makespan, planning, times, modes: vec![], workers: vec![], capacities: vec![], decoder: None }
+    }
+
+    /**
+    * Create the solution of a state.
+    * state: state to save.
+    * instance: name of the instance solved.
+    * interruptions: interruptions used to create the pre-emptive project.
+    */
+    pub fn from_state(state: &SaState, instance: &str, interruptions: u32) -> Solution {
+        let mut solution = Solution::new(instance.to_string(), interruptions, state.get_makespan(), state.planning.clone(), state.times.clone());
+        solution.decoder = Some(state.decoder);
+        if !state.project.multi_mode_activities().is_empty() {
+            solution.modes = state.get_modes();
+        }
+        if !state.project.workers.is_empty() {
+            solution.workers = state.planning.iter()
+                                             .map(|id| state.project.activities.iter().find(|x| x.id == *id).map_or(vec![], |x| x.workers.clone()))
+                                             .collect();
+        }
+        if state.objective == Objective::CapacityCost {
+            solution.capacities = state.project.resources.iter().map(|x| x.capacity).collect();
+        }
+        solution
+    }
+
+    /**
+    * Select in project the mode of each activity in the solution.
+    * project: pre-emptive project of the solution.
+    */
+    pub fn set_modes(&self, project: &mut Project) -> Result<(), String> {
+        for (id, mode) in self.planning.iter().zip(self.modes.iter()) {
+            let activity = match project.activities.iter().find(|x| x.id == *id) {
+                Some(activity) => activity,
+                None => return Err(format!("La actividad {} no esta en el proyecto", id)),
+            };
+            if *mode as usize >= activity.modes.len().max(1) {
+                return Err(format!("La actividad {} no tiene el modo {}", id, mode));
+            }
+            project.set_mode(activity.get_parent(), *mode as usize);
+        }
+        Ok(())
+    }
+
+    /**
+    * Assign in project the workers of each activity in the solution.
+    * project: pre-emptive project of the solution.
+    */
+    pub fn set_workers(&self, project: &mut Project) -> Result<(), String> {
+        for (id, workers) in self.planning.iter().zip(self.workers.iter()) {
+            let activity = project.activities.iter_mut()
+                                             .find(|x| x.id == *id)
+                                             .ok_or_else(|| format!("La actividad {} no esta en el proyecto", id))?;
+            activity.workers = workers.clone();
+        }
+        Ok(())
+    }
+
+    /**
+    * Set in project the capacity of each resource in the solution, if it has them.
+    * project: project of the solution.
+    */
+    pub fn set_capacities(&self, project: &mut Project) -> Result<(), String> {
+        if self.capacities.is_empty() {
+            return Ok(());
+        }
+        if self.capacities.len() != project.resources.len() {
+            return Err(format!("La solucion tiene {} capacidades y el proyecto {} recursos", self.capacities.len(), project.resources.len()));
+        }
+        for (resource, capacity) in project.resources.iter_mut().zip(self.capacities.iter()) {
+            resource.capacity = *capacity;
+        }
+        Ok(())
+    }
+
+    /**
+    * Return the solution in text format.
+    */
+    pub fn get_string(&self) -> String {
+        let planning : Vec<String> = self.planning.iter().map(|x| x.to_string()).collect();
+        let times : Vec<String> = self.times.iter().map(|x| x.to_string()).collect();
+        let mut content = String::from("# Solucion PRCPSP\n");
+        content.push_str(&format!("instance {}\n", self.instance));
+        content.push_str(&format!("interruptions {}\n", self.interruptions));
+        content.push_str(&format!("makespan {}\n", self.makespan));
+        content.push_str(&format!("planning {}\n", planning.join(" ")));
+        content.push_str(&format!("times {}\n", times.join(" ")));
+        if !self.modes.is_empty() {
+            let modes : Vec<String> = self.modes.iter().map(|x| x.to_string()).collect();
+            content.push_str(&format!("modes {}\n", modes.join(" ")));
+        }
+        if !self.workers.is_empty() {
+            let workers : Vec<String> = self.workers.iter()
+                                                    .map(|x| if x.is_empty() { String::from("-") } else {
+                                                        x.iter().map(|w| w.to_string()).collect::<Vec<String>>().join(",")
+                                                    })
+                                                    .collect();
+            content.push_str(&format!("workers {}\n", workers.join(" ")));
+        }
+        if !self.capacities.is_empty() {
+            let capacities : Vec<String> = self.capacities.iter().map(|x| x.to_string()).collect();
+            content.push_str(&format!("capacities {}\n", capacities.join(" ")));
+        }
+        if let Some(decoder) = self.decoder {
+            content.push_str(&format!("decoder {}\n", decoder));
+        }
+        content
+    }
+
+    /**
+    * Write the solution in a file.
+    * filename: path of the file.
+    */
+    pub fn write(&self, filename: &str) -> Result<(), String> {
+        fs::write(filename, self.get_string().as_bytes())
+            .map_err(|e| format!("No se pudo escribir el archivo {}: {}", filename, e))
+    }
+
+    /**
+    * Read a solution from a file.
+    * filename: path of the file.
+    */
+    pub fn read(filename: &str) -> Result<Solution, String> {
+        let contents = fs::read_to_string(filename)
+                .map_err(|e| format!("No se pudo leer el archivo {}: {}", filename, e))?;
+        Solution::parse(&contents)
+    }
+
+    /**
+    * Create a solution from its text format.
+    * contents: text of the solution.
+    */
+    pub fn parse(contents: &str) -> Result<Solution, String> {
+        let mut solution = Solution::new(String::new(), 0, 0, vec![], vec![]);
+        for line in contents.lines().map(|x| x.trim()).filter(|x| !x.is_empty() && !x.starts_with('#')) {
+            let (key, value) = match line.find(char::is_whitespace) {
+                Some(i) => (&line[..i], line[i..].trim()),
+                None => (line, ""),
+            };
+            match key {
+                "instance" => solution.instance = value.to_string(),
+                "interruptions" => solution.interruptions = value.parse::<u32>().map_err(|_| format!("Valor invalido en '{}'", line))?,
+                "makespan" => solution.makespan = value.parse::<u32>().map_err(|_| format!("Valor invalido en '{}'", line))?,
+                "planning" => solution.planning = values::<u32>(value, line)?,
+                "times" => solution.times = values::<i32>(value, line)?,
+                "modes" => solution.modes = values::<u32>(value, line)?,
+                "workers" => solution.workers = value.split_whitespace()
+                                                     .map(|x| if x == "-" { Ok(vec![]) } else { values::<u32>(&x.replace(',', " "), line) })
+                                                     .collect::<Result<Vec<Vec<u32>>, String>>()?,
+                "capacities" => solution.capacities = values::<u32>(value, line)?,
+                "decoder" => solution.decoder = Some(Decoder::from_str(value).ok_or_else(|| format!("Decodificador invalido en '{}'", line))?),
+                _ => return Err(format!("Linea desconocida '{}'", line)),
+            }
+        }
+        if solution.planning.is_empty() || solution.planning.len() != solution.times.len() {
+            return Err(String::from("La solucion debe tener la lista de actividades y sus tiempos de inicio"));
+        }
+        if !solution.modes.is_empty() && solution.modes.len() != solution.planning.len() {
+            return Err(String::from("La solucion debe tener un modo por actividad"));
+        }
+        if !solution.workers.is_empty() && solution.workers.len() != solution.planning.len() {
+            return Err(String::from("La solucion debe tener los trabajadores de cada actividad"));
+        }
+        Ok(solution)
+    }
+}
+
+/**
+* Parse every token in a value as a number.
+*/
+fn values<T: std::str::FromStr>(value: &str, line: &str) -> Result<Vec<T>, String> {
+    value.split_whitespace()
+         .map(|x| x.parse::<T>().map_err(|_| format!("Valor invalido '{}' en '{}'", x, line)))
+         .collect()
+}
+
+#[cfg(test)]
+ mod tests {
+     use crate::prcpsp::solution::Solution as Solution;
+     use crate::prcpsp::sa_state::SaState as SaState;
+     use crate::prcpsp::random_rcpsp as random_rcpsp;
+     use crate::prcpsp::decoder::Decoder as Decoder;
+     use crate::metaheuristics::tabu_search::tabu_search as ts;
+     use crate::metaheuristics::state::State as State;
+
+     #[test]
+     fn test_warm_start() {
+         let mut project = random_rcpsp::get_random_rcpsp(14, 30, 10, 5, 20);
+         for a in &mut project.activities {
+             a.start_time = -1;
+         }
+         let project = project.pre_emptive_project(1);
+         let initial = SaState::new(project.clone(), 3);
+         let (best, _) = ts::tabu_search(initial, 5, 5, 5);
+
+         let state = SaState::from_planning(project.clone(), best.planning.clone(), 3).unwrap();
+         let solution = Solution::from_state(&state, "prcpsp-10", 1);
+         let other = Solution::parse(&solution.get_string()).unwrap();
+         assert_eq!(other.instance, "prcpsp-10");
+         assert_eq!(other.interruptions, 1);
+         assert_eq!((0, other.makespan as f64), best.get_cost());
+         assert_eq!(other.planning, best.planning);
+         assert_eq!(other.times, best.times);
+         assert_eq!(other.decoder, Some(Decoder::Serial));
+
+         let loaded = SaState::from_planning(project.clone(), other.planning, 3).unwrap();
+         assert_eq!(loaded.times, best.times);
+
+         let mut reversed = best.planning.clone();
+         reversed.reverse();
+         assert!(SaState::from_planning(project.clone(), reversed, 3).is_err());
+         assert!(SaState::from_planning(project.clone(), vec![1,2], 3).is_err());
+
+         let mut state = SaState::from_planning(project.clone(), best.planning.clone(), 3).unwrap();
+         state.set_decoder(Decoder::Parallel);
+         let other = Solution::parse(&Solution::from_state(&state, "prcpsp-10", 1).get_string()).unwrap();
+         assert_eq!(other.decoder, Some(Decoder::Parallel));
+         assert_eq!(other.times, state.times);
+         let text = solution.get_string().replace("decoder serial", "decoder random");
+         assert!(Solution::parse(&text).is_err());
+         let mut solution = solution;
+         solution.decoder = None;
+         assert_eq!(Solution::parse(&solution.get_string()).unwrap().decoder, None);
+     }
+ }
@@ -0,0 +1,282 @@
+use std::fmt;
+use std::fs;
+
+use rand::{Rng, SeedableRng, StdRng};
+use rand::distributions::Distribution as Sampler;
+use rand::distributions::Gamma;
+
+use crate::prcpsp::project::Project as Project;
+use crate::prcpsp::sa_state::SaState as SaState;
+use crate::prcpsp::decoder::Decoder as Decoder;
+
+/**
+* Distribution of the duration of an activity.
+*/
+#[derive(Debug,Clone,PartialEq)]
+pub enum Distribution {
+    /* Triangular distribution (minimum, mode, maximum). */
+    Triangular(f64, f64, f64),
+    /* Beta-PERT distribution (optimistic, most likely, pessimistic). */
+    Pert(f64, f64, f64),
+    /* Durations observed, each one equally likely. */
+    Empirical(Vec<u32>),
+}
+
+impl Distribution {
+    /**
+    * Return a duration sampled from the distribution, rounded to the nearest time unit.
+    * rng: generator of pseudo-random numbers.
+    */
+    pub fn sample<R: Rng>(&self, rng: &mut R) -> u32 {
+        let value = match self {
+            Distribution::Triangular(min, mode, max) => {
+                if max <= min {
+                    *min
+                } else {
+                    let u = rng.gen::<f64>();
+                    if u < (mode - min) / (max - min) {
+                        min + (u * (max - min) * (mode - min)).sqrt()
+                    } else {
+                        max - ((1.0 - u) * (max - min) * (max - mode)).sqrt()
+                    }
+                }
+            }
+            Distribution::Pert(min, mode, max) => {
+                if max <= min {
+                    *min
+                } else {
+                    let alpha = 1.0 + 4.0 * (mode - min) / (max - min);
+                    let beta = 1.0 + 4.0 * (max - mode) / (max - min);
+                    let x = Gamma::new(alpha, 1.0).sample(rng);
+                    let y = Gamma::new(beta, 1.0).sample(rng);
+                    min + (max - min) * x / (x + y)
+                }
+            }
+            Distribution::Empirical(samples) => samples[rng.gen_range(0, samples.len())] as f64,
+        };
+        value.round().max(0.0) as u32
+    }
+}
+
+impl fmt::Display for Distribution {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Distribution::Triangular(min, mode, max) => write!(f, "triangular {} {} {}", min, mode, max),
+            Distribution::Pert(min, mode, max) => write!(f, "pert {} {} {}", min, mode, max),
+            Distribution::Empirical(samples) => {
+                let samples : Vec<String> = samples.iter().map(|x| x.to_string()).collect();
+                write!(f, "empirical {}", samples.join(" "))
+            }
+        }
+    }
+}
+
+/**
+* Makespans of an activity list over sampled scenarios of durations, in increasing order.
+*/
+#[derive(Debug,Clone)]
+pub struct Simulation {
+    pub makespans: Vec<u32>,
+}
+
+impl Simulation {
+    /**
+    * Return the mean makespan of the scenarios.
+    */
+    pub fn expected(&self) -> f64 {
+        if self.makespans.is_empty() {
+            return 0.0;
+        }
+        self.makespans.iter().map(|x| *x as f64).sum::<f64>() / self.makespans.len() as f64
+    }
+
+    /**
+    * Return the smallest makespan of at least p percent of the scenarios.
+    * p: percent, from 0 to 100.
+    */
+    pub fn percentile(&self, p: u32) -> u32 {
+        if self.makespans.is_empty() {
+            return 0;
+        }
+        let rank = (p.min(100) as usize * self.makespans.len()).div_ceil(100).max(1);
+        self.makespans[rank - 1]
+    }
+
+    /**
+    * Return the probability of finishing by a deadline, as the fraction of scenarios that do it.
+    * deadline: time when the project should be finished.
+    */
+    pub fn on_time(&self, deadline: i32) -> f64 {
+        if self.makespans.is_empty() {
+            return 0.0;
+        }
+        self.makespans.iter().filter(|x| **x as i32 <= deadline).count() as f64 / self.makespans.len() as f64
+    }
+}
+
+/**
+* Replay an activity list through a decoder over sampled scenarios, where each
+* activity with a distribution takes a duration sampled from it, split among its subactivities.
+* The distribution of a multi-mode activity is the one of its first mode, so the samples
+* are scaled by the duration of the selected mode over the duration of the first one.
+* With the same seed every activity list of a project is evaluated over the same scenarios.
+* project: pre-emptive project with the modes selected.
+* planning: activity list.
+* scenarios: number of scenarios.
+* seed: seed for pseudo-random numbers.
+* decoder: schedule generation scheme.
+*/
+pub fn simulate(project: &Project, planning: &[u32], scenarios: u32, seed: u64, decoder: Decoder) -> Result<Simulation, String> {
+    let mut rng : StdRng = SeedableRng::seed_from_u64(seed);
+    let mut uncertain : Vec<(u32, &Distribution, f64)> = vec![];
+    for activity in &project.activities {
+        if let Some(distribution) = &activity.distribution {
+            if !uncertain.iter().any(|(x, _, _)| *x == activity.get_parent()) {
+                let (selected, first) = project.activities.iter()
+                                                          .filter(|x| x.get_parent() == activity.get_parent() && !x.modes.is_empty())
+                                                          .fold((0, 0), |(s, f), x| (s + x.modes[x.mode].duration, f + x.modes[0].duration));
+                let scale = if first == 0 { 1.0 } else { selected as f64 / first as f64 };
+                uncertain.push((activity.get_parent(), distribution, scale));
+            }
+        }
+    }
+    let mut makespans : Vec<u32> = vec![];
+    for _ in 0..scenarios {
+        let mut scenario = project.clone();
+        for (parent, distribution, scale) in &uncertain {
+            scenario.set_duration(*parent, (distribution.sample(&mut rng) as f64 * scale).round() as u32);
+        }
+        let mut state = SaState::from_planning(scenario, planning.to_vec(), seed)?;
+        if decoder != Decoder::Serial {
+            state.set_decoder(decoder);
+        }
+        makespans.push(state.get_makespan());
+    }
+    makespans.sort_unstable();
+    Ok(Simulation { makespans })
+}
+
+/*
+* Distributions of the durations of activities.
+*
+* File format (lines starting with '#' are comments):
+*
+*   <activity> triangular <min> <mode> <max>
+*   <activity> pert <optimistic> <most_likely> <pessimistic>
+*   <activity> empirical <d_1> .. <d_n>
+*
+* where activity is the id or name of an activity. The nominal duration
+* of the activity is still used by the metaheuristics. For a multi-mode activity
+* the distribution is the one of its first mode.
+*/
+
+/**
+* Read a file of duration distributions and set them to the activities of a project.
+* project: project whose activities are changed.
+* filename: path to the distributions.
+*/
+pub fn read_distributions(project: &mut Project, filename: &str) -> Result<(), String> {
+    let contents = fs::read_to_string(filename)
+            .map_err(|e| format!("No se pudo leer el archivo {}: {}", filename, e))?;
+    parse_distributions(project, &contents)
+}
+
+/**
+* Set the duration distributions of a text to the activities of a project.
+* project: project whose activities are changed.
+* contents: text of the distributions.
+*/
+pub fn parse_distributions(project: &mut Project, contents: &str) -> Result<(), String> {
+    for line in contents.lines().map(|x| x.trim()).filter(|x| !x.is_empty() && !x.starts_with('#')) {
+        let tokens : Vec<&str> = line.split_whitespace().collect();
+        if tokens.len() < 3 {
+            return Err(format!("Distribucion incompleta: '{}'", line));
+        }
+        let values = tokens[2..].iter()
+                                .map(|x| x.parse::<u32>().map_err(|_| format!("Valor invalido '{}' en '{}'", x, line)))
+                                .collect::<Result<Vec<u32>, String>>()?;
+        let distribution = match (tokens[1].to_lowercase().as_str(), values.as_slice()) {
+            ("empirical", _) => Distribution::Empirical(values.clone()),
+            ("triangular", [min, mode, max]) if min <= mode && mode <= max => Distribution::Triangular(*min as f64, *mode as f64, *max as f64),
+            ("pert", [min, mode, max]) if min <= mode && mode <= max => Distribution::Pert(*min as f64, *mode as f64, *max as f64),
+            _ => return Err(format!("Distribucion invalida '{}'", line)),
+        };
+        let activity = project.activities.iter_mut()
+                                         .find(|x| x.id.to_string() == tokens[0] || x.name == tokens[0])
+                                         .ok_or_else(|| format!("Actividad {} no encontrada en '{}'", tokens[0], line))?;
+        activity.distribution = Some(distribution);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+ mod tests {
+     use rand::{SeedableRng, StdRng};
+
+     use crate::prcpsp::stochastic as stochastic;
+     use crate::prcpsp::stochastic::Distribution as Distribution;
+     use crate::prcpsp::stochastic::Simulation as Simulation;
+     use crate::prcpsp::psplib as psplib;
+     use crate::prcpsp::instance as instance;
+     use crate::prcpsp::sa_state::SaState as SaState;
+     use crate::prcpsp::decoder::Decoder as Decoder;
+     use crate::prcpsp::mode::Mode as Mode;
+
+     #[test]
+     fn test_distributions() {
+         let mut rng : StdRng = SeedableRng::seed_from_u64(3);
+         for distribution in &[Distribution::Triangular(2.0, 3.0, 8.0), Distribution::Pert(2.0, 3.0, 8.0), Distribution::Empirical(vec![2, 8, 3])] {
+             let samples : Vec<u32> = (0..1000).map(|_| distribution.sample(&mut rng)).collect();
+             assert!(samples.iter().all(|x| *x >= 2 && *x <= 8));
+         }
+         let samples : Vec<u32> = (0..100).map(|_| Distribution::Empirical(vec![2, 8, 3]).sample(&mut rng)).collect();
+         assert!(samples.contains(&2) && samples.contains(&3) && samples.contains(&8));
+         let mean = |distribution: Distribution, rng: &mut StdRng| (0..2000).map(|_| distribution.sample(rng) as f64).sum::<f64>() / 2000.0;
+         assert!((mean(Distribution::Triangular(2.0, 3.0, 10.0), &mut rng) - 5.0).abs() < 0.3);
+         assert!((mean(Distribution::Pert(2.0, 3.0, 10.0), &mut rng) - 4.0).abs() < 0.3);
+         assert_eq!(Distribution::Pert(4.0, 4.0, 4.0).sample(&mut rng), 4);
+
+         let simulation = Simulation { makespans: vec![10, 11, 11, 12, 15] };
+         assert_eq!(simulation.expected(), 11.8);
+         assert_eq!((simulation.percentile(50), simulation.percentile(80), simulation.percentile(100)), (11, 12, 15));
+         assert_eq!(simulation.on_time(11), 0.6);
+     }
+
+     #[test]
+     fn test_simulate() {
+         let mut project = psplib::read_sm("examples/psplib-9.sm").unwrap();
+         stochastic::parse_distributions(&mut project, "# fechas inciertas\n4 empirical 4 6\n7 pert 2 4 9\n").unwrap();
+         let pre_emptive = project.pre_emptive_project(1);
+         let state = SaState::new(pre_emptive.clone(), 14);
+         let simulation = stochastic::simulate(&pre_emptive, &state.planning, 200, 0, Decoder::Serial).unwrap();
+         assert_eq!(simulation.makespans.len(), 200);
+         assert!(simulation.makespans.windows(2).all(|x| x[0] <= x[1]));
+         assert!(simulation.percentile(0) < simulation.percentile(100));
+         assert_eq!(simulation.makespans, stochastic::simulate(&pre_emptive, &state.planning, 200, 0, Decoder::Serial).unwrap().makespans);
+         assert_eq!(stochastic::simulate(&pre_emptive, &state.planning, 20, 0, Decoder::Parallel).unwrap().makespans.len(), 20);
+
+         // Without distributions every scenario is the nominal schedule
+         let nominal = stochastic::simulate(&psplib::read_sm("examples/psplib-9.sm").unwrap().pre_emptive_project(1), &state.planning, 5, 0, Decoder::Serial).unwrap();
+         assert_eq!(nominal.makespans, vec![state.get_makespan(); 5]);
+
+         let other = instance::parse_instance(&instance::to_string(&project)).unwrap();
+         assert_eq!(other.activities[3].distribution, Some(Distribution::Empirical(vec![4, 6])));
+         assert_eq!(other.activities[6].distribution, Some(Distribution::Pert(2.0, 4.0, 9.0)));
+
+         // The second mode of 4 takes twice as long, so its samples do too
+         let mut multi_mode = psplib::read_sm("examples/psplib-9.sm").unwrap();
+         let first = Mode::new(1, multi_mode.activities[3].duration, multi_mode.activities[3].supplies.clone(), vec![]);
+         let second = Mode::new(2, 2 * first.duration, first.supplies.clone(), vec![]);
+         stochastic::parse_distributions(&mut multi_mode, &format!("4 empirical {}\n", first.duration)).unwrap();
+         multi_mode.activities[3].modes = vec![first, second];
+         multi_mode.activities[3].set_mode(1);
+         let pre_emptive = multi_mode.pre_emptive_project(0);
+         let state = SaState::new(pre_emptive.clone(), 14);
+         let simulation = stochastic::simulate(&pre_emptive, &state.planning, 5, 0, Decoder::Serial).unwrap();
+         assert_eq!(simulation.makespans, vec![state.get_makespan(); 5]);
+
+         assert!(stochastic::parse_distributions(&mut project, "4 triangular 3 2 5\n").is_err());
+         assert!(stochastic::parse_distributions(&mut project, "4 pert 1 2\n").is_err());
+         assert!(stochastic::parse_distributions(&mut project, "4 normal 1 2 3\n").is_err());
+     }
+ }
@@ -20,7 +20,7 @@ pub fn get_time(seconds: u64) -> String {
         return time;
 }
 
-pub fn write_log_random(random_seed: u64, random_cost: u32, random_activities: u32, random_resources: u32, random_resources_max_capacity: u32, initial_cost: u32){
+pub fn write_log_random(random_seed: u64, random_cost: u32, random_activities: u32, random_resources: u32, random_resources_max_capacity: u32, initial_cost: String){
     let mut content  = String::new();
     content.push_str("\n Datos del ejemplar: \n");
     content.push_str("  Semilla: ");
@@ -39,7 +39,7 @@ pub fn write_log_random(random_seed: u64, random_cost: u32, random_activities: u
     content.push_str(&random_resources_max_capacity.to_string());
     content.push_str(&", ");
     content.push_str("Costo inicial: ");
-    content.push_str(&initial_cost.to_string());
+    content.push_str(&initial_cost);
     if !std::path::Path::new(LOG_PATH).is_file() {
         fs::File::create(LOG_PATH).expect("No se pudo crear un archivo");
         fs::write(LOG_PATH, content.as_bytes()).expect("No se pudó escribir un archivo");
@@ -53,7 +53,92 @@ pub fn write_log_random(random_seed: u64, random_cost: u32, random_activities: u
     }
 }
 
-pub fn write_log_sa(state: String, cost: u32, iterations: u32, temperature: f32, epsilon: f32, decrement: f32, seed: u64, log: Vec<String>, time: String, m: u32){
+/**
+* Write the data of an instance read from a file in log.
+* filename: path to the instance.
+* initial_cost: cost of the initial solution.
+*/
+pub fn write_log_file(filename: &str, initial_cost: String) {
+    let mut content = String::new();
+    content.push_str("\n Datos del ejemplar: \n");
+    content.push_str("  Archivo: ");
+    content.push_str(filename);
+    content.push_str(", ");
+    content.push_str("Costo inicial: ");
+    content.push_str(&initial_cost);
+    append_log(content);
+}
+
+/**
+* Write the gap between the best solution found and the best known makespan in log.
+* best_known: best known makespan.
+* gap: gap in percent.
+*/
+pub fn write_log_gap(best_known: u32, gap: f32) {
+    let mut content = String::new();
+    content.push_str(", ");
+    content.push_str("Mejor conocido: ");
+    content.push_str(&best_known.to_string());
+    content.push_str(", ");
+    content.push_str("Brecha: ");
+    content.push_str(&format!("{:.2}%", gap));
+    append_log(content);
+}
+
+/**
+* Write the decoder of the run in log.
+* decoder: name of the decoder.
+*/
+pub fn write_log_decoder(decoder: &str) {
+    let mut content = String::new();
+    content.push_str(", ");
+    content.push_str("Decodificador: ");
+    content.push_str(decoder);
+    append_log(content);
+}
+
+/**
+* Write the evaluation of the best solution over sampled scenarios of durations in log.
+* scenarios: number of scenarios.
+* expected: mean makespan.
+* percentiles: pairs (percent, makespan).
+* deadline: deadline of the project.
+* on_time: probability of finishing by the deadline.
+*/
+pub fn write_log_simulation(scenarios: u32, expected: f64, percentiles: &[(u32, u32)], deadline: i32, on_time: f64) {
+    let mut content = String::new();
+    content.push_str(", ");
+    content.push_str("Escenarios: ");
+    content.push_str(&scenarios.to_string());
+    content.push_str(", ");
+    content.push_str("Makespan esperado: ");
+    content.push_str(&format!("{:.2}", expected));
+    for (p, makespan) in percentiles {
+        content.push_str(&format!(", P{}: {}", p, makespan));
+    }
+    content.push_str(", ");
+    content.push_str(&format!("Probabilidad en {}: {:.2}%", deadline, 100.0 * on_time));
+    append_log(content);
+}
+
+/**
+* Append content at the end of log file, creating it if doesn't exist.
+*/
+fn append_log(content: String) {
+    if !std::path::Path::new(LOG_PATH).is_file() {
+        fs::File::create(LOG_PATH).expect("No se pudo crear un archivo");
+        fs::write(LOG_PATH, content.as_bytes()).expect("No se pudó escribir un archivo");
+    } else {
+        let mut file = fs::OpenOptions::new()
+        .append(true)
+        .open(LOG_PATH)
+        .unwrap();
+        write!(file, "{}", content).expect("No se pudo escribir un archivo");
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn write_log_sa(state: String, cost: String, iterations: u32, temperature: f32, epsilon: f32, decrement: f32, seed: u64, log: Vec<String>, time: String, m: u32){
     let mut content  = String::new();
     content.push_str("\n >>>>>>>>>>> Ejemplar: \n");
     content.push_str(&state);
@@ -63,7 +148,7 @@ pub fn write_log_sa(state: String, cost: u32, iterations: u32, temperature: f32,
     content.push_str("Recocido Simulado");
     content.push_str(&", ");
     content.push_str("Costo: ");
-    content.push_str(&cost.to_string());
+    content.push_str(&cost);
     content.push_str(&", ");
     content.push_str("Semilla: ");
     content.push_str(&seed.to_string());
@@ -99,7 +184,8 @@ pub fn write_log_sa(state: String, cost: u32, iterations: u32, temperature: f32,
     }
 }
 
-pub fn write_log_ts(state: String, cost: u32, tabu_time: u32, neighbors: u32, iterations: u32, seed: u64, log: Vec<String>, time: String, m: u32){
+#[allow(clippy::too_many_arguments)]
+pub fn write_log_ts(state: String, cost: String, tabu_time: u32, neighbors: u32, iterations: u32, seed: u64, log: Vec<String>, time: String, m: u32){
     let mut content  = String::new();
     content.push_str("\n >>>>>>>>>>> Ejemplar: \n");
     content.push_str(&state);
@@ -109,7 +195,7 @@ pub fn write_log_ts(state: String, cost: u32, tabu_time: u32, neighbors: u32, it
     content.push_str("Busqueda Tabu");
     content.push_str(", ");
     content.push_str("Costo: ");
-    content.push_str(&cost.to_string());
+    content.push_str(&cost);
     content.push_str(&", ");
     content.push_str("Semilla: ");
     content.push_str(&seed.to_string());
@@ -149,6 +235,17 @@ pub fn write_svg(content: String, name: &str){
     fs::write(&(IMAGE_PATH.to_owned() + name), content.as_bytes()).expect("No se pudó escribir un archivo");
 }
 
+/**
+* Return the path of a file in output dir, creating the dir if doesn't exist.
+* name: name of the file.
+*/
+pub fn output_path(name: &str) -> String {
+    if !std::path::Path::new(IMAGE_PATH).is_dir() {
+        fs::create_dir(IMAGE_PATH).expect("Ocurrio error");
+    }
+    IMAGE_PATH.to_owned() + name
+}
+
 /**
 * Given a vector with the cost of best state in each iteration,
 * create a file with the (x,y) coordinates.
@@ -168,6 +265,25 @@ pub fn get_log(log : Vec<String>)  {
     fs::write(HISTORY_PATH, content.as_bytes()).expect("No se pudó escribir un archivo");
 }
 
+/**
+* Return the value of an option given as '--option value' in arguments.
+* args: program arguments.
+* option: name of the option.
+*/
+pub fn get_option(args: &[String], option: &str) -> Option<String> {
+    let position = args.iter().position(|x| x == option)?;
+    args.get(position + 1).cloned()
+}
+
+/**
+* Return the name of an instance, the name of its file without extension.
+* filename: path to the instance.
+*/
+pub fn instance_name(filename: &str) -> String {
+    let path = std::path::Path::new(filename);
+    path.file_stem().map_or(filename.to_string(), |x| x.to_string_lossy().to_string())
+}
+
 pub fn read_random_prcpsp(filename: String) -> Vec<u32> {
     let contents = fs::read_to_string(filename)
             .expect("Ocurrio un error al leer el archivo");
@@ -0,0 +1,288 @@
+use std::fmt;
+
+use crate::prcpsp::project::Project as Project;
+use crate::prcpsp::project as project;
+use crate::prcpsp::activity::Activity as Activity;
+use crate::prcpsp::relation::RelationType as RelationType;
+use crate::prcpsp::worker::Worker as Worker;
+use crate::prcpsp::worker as worker;
+
+/**
+* A constraint broken by a schedule.
+*/
+#[derive(Debug,Clone,PartialEq)]
+pub enum Violation {
+    /* Activity of project without start time. */
+    Missing(u32),
+    /* Activity in schedule which isn't in project. */
+    Unknown(u32),
+    /* Activity with more than one start time. */
+    Repeated(u32),
+    /* Activity starting before its predecessor finishes (activity, start, predecessor, finish). */
+    Precedence(u32, i32, u32, i32),
+    /* Resource over its capacity (resource, time, demand, capacity). */
+    Capacity(u32, i32, u32, u32),
+    /* Dummy activity which isn't the first or last one in schedule. */
+    Dummy(u32),
+    /* Non-renewable resource consumed over its capacity (resource, consumption, capacity). */
+    NonRenewable(u32, u32, u32),
+    /* Relation with its lags missed (from, to, type, time units). */
+    Relation(u32, u32, RelationType, u32),
+    /* Activity starting before its release time (activity, start, release). */
+    Release(u32, i32, i32),
+    /* Activity finishing after its deadline (activity, finish, deadline). */
+    Deadline(u32, i32, i32),
+    /* Segment of an activity shorter than its minimum (activity, start, length, minimum). */
+    Segment(u32, i32, i32, u32),
+    /* Gap between segments of an activity longer than its maximum (activity, finish, start, maximum). */
+    Gap(u32, i32, i32, i32),
+    /* Activity whose workers don't have the skills it needs. */
+    Assignment(u32),
+    /* Worker assigned to activities executed at the same time (worker, activity, other). */
+    Worker(u32, u32, u32),
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Violation::Missing(id) => write!(f, "La actividad {} no tiene tiempo de inicio", id),
+            Violation::Unknown(id) => write!(f, "La actividad {} no esta en el proyecto", id),
+            Violation::Repeated(id) => write!(f, "La actividad {} tiene mas de un tiempo de inicio", id),
+            Violation::Precedence(id, start, pred, finish) => write!(f, "La actividad {} inicia en {} antes de que su predecesor {} termine en {}", id, start, pred, finish),
+            Violation::Capacity(resource, time, demand, capacity) => write!(f, "El recurso {} en el tiempo {} tiene demanda {} y capacidad {}", resource, time, demand, capacity),
+            Violation::Dummy(id) => write!(f, "La actividad ficticia {} no esta al inicio o final", id),
+            Violation::NonRenewable(resource, consumption, capacity) => write!(f, "El recurso no renovable {} tiene consumo {} y capacidad {}", resource, consumption, capacity),
+            Violation::Relation(from, to, kind, excess) => write!(f, "La relacion {} de {} a {} no cumple sus retrasos por {} unidades", kind, from, to, excess),
+            Violation::Release(id, start, release) => write!(f, "La actividad {} inicia en {} antes de su liberacion en {}", id, start, release),
+            Violation::Deadline(id, finish, deadline) => write!(f, "La actividad {} termina en {} despues de su fecha limite {}", id, finish, deadline),
+            Violation::Segment(id, start, length, min) => write!(f, "La actividad {} tiene un segmento en {} de duracion {} menor al minimo {}", id, start, length, min),
+            Violation::Gap(id, finish, start, max) => write!(f, "La actividad {} se interrumpe de {} a {}, mas que el maximo {}", id, finish, start, max),
+            Violation::Assignment(id) => write!(f, "Los trabajadores de la actividad {} no tienen las habilidades que necesita", id),
+            Violation::Worker(worker, id, other) => write!(f, "El trabajador {} esta en las actividades {} y {} al mismo tiempo", worker, id, other),
+        }
+    }
+}
+
+/**
+* Check a schedule against the project, without using the decoder.
+* Return every violation found, empty if the schedule is feasible.
+* Subactivities that start after an interruption are checked with their resume overhead.
+* In projects with workers, the workers assigned to each activity are checked too.
+* project: project scheduled.
+* planning: activity list.
+* times: start time of each activity in planning.
+*/
+pub fn validate(project: &Project, planning: &[u32], times: &[i32]) -> Vec<Violation> {
+    let mut violations : Vec<Violation> = vec![];
+    let mut starts : Vec<(u32, i32)> = vec![];
+    for (id, time) in planning.iter().zip(times.iter()) {
+        if !project.activities.iter().any(|x| x.id == *id) {
+            violations.push(Violation::Unknown(*id));
+        } else if starts.iter().any(|(x, _)| x == id) {
+            violations.push(Violation::Repeated(*id));
+        } else if *time < 0 {
+            violations.push(Violation::Missing(*id));
+        } else {
+            starts.push((*id, *time));
+        }
+    }
+    let start_time = |id: u32| starts.iter().find(|(x, _)| *x == id).map(|(_, t)| *t);
+
+    // Subactivities starting after an interruption take their resume overhead
+    let mut project = project.clone();
+    for i in 0..project.activities.len() {
+        let mut activity = project.activities[i].clone();
+        activity.set_resumed(false);
+        let previous = project.previous_piece(&activity).and_then(|x| start_time(x.id).map(|t| t + x.duration as i32));
+        let resumed = match (previous, start_time(activity.id)) {
+            (Some(finish), Some(start)) => activity.duration > 0 && start > finish,
+            _ => false,
+        };
+        activity.set_resumed(resumed);
+        project.activities[i] = activity;
+    }
+    let project = &project;
+
+    for activity in &project.activities {
+        let start = match start_time(activity.id) {
+            Some(start) => start,
+            None => {
+                if !violations.contains(&Violation::Missing(activity.id)) {
+                    violations.push(Violation::Missing(activity.id));
+                }
+                continue;
+            }
+        };
+        if start < activity.release {
+            violations.push(Violation::Release(activity.id, start, activity.release));
+        }
+        let finish = start + activity.duration as i32;
+        if activity.deadline >= 0 && finish > activity.deadline {
+            violations.push(Violation::Deadline(activity.id, finish, activity.deadline));
+        }
+        for pred in &activity.predecessors {
+            let predecessor = project.activities.iter().find(|x| x.id == *pred).unwrap();
+            if let Some(pred_start) = start_time(*pred) {
+                let finish = pred_start + predecessor.duration as i32;
+                if finish > start {
+                    violations.push(Violation::Precedence(activity.id, start, *pred, finish));
+                }
+            }
+        }
+    }
+
+    let first = project.activities.first().unwrap();
+    let last = project.activities.last().unwrap();
+    let makespan = starts.iter()
+                         .map(|(id, t)| t + project.activities.iter().find(|x| x.id == *id).unwrap().duration as i32)
+                         .max()
+                         .unwrap_or(0);
+    if let Some(start) = start_time(first.id) {
+        if planning.first() != Some(&first.id) || starts.iter().any(|(_, t)| *t < start) {
+            violations.push(Violation::Dummy(first.id));
+        }
+    }
+    if let Some(start) = start_time(last.id) {
+        if planning.last() != Some(&last.id) || start < makespan {
+            violations.push(Violation::Dummy(last.id));
+        }
+    }
+
+    for resource in &project.resources {
+        for time in 0..makespan {
+            let demand : u32 = starts.iter()
+                                     .map(|(id, t)| (project.activities.iter().find(|x| x.id == *id).unwrap(), *t))
+                                     .filter(|(a, t)| *t <= time && time < t + a.duration as i32)
+                                     .flat_map(|(a, _)| a.supplies.iter())
+                                     .filter(|x| x.resource == *resource)
+                                     .map(|x| x.usage)
+                                     .sum();
+            if demand > resource.capacity_at(time) {
+                violations.push(Violation::Capacity(resource.id, time, demand, resource.capacity_at(time)));
+            }
+        }
+    }
+
+    for relation in &project.relations {
+        let duration = |id: u32| project.activities.iter().find(|x| x.id == id).map_or(0, |x| x.duration);
+        if let (Some(from), Some(to)) = (start_time(relation.from), start_time(relation.to)) {
+            let excess = relation.excess(from, duration(relation.from), to, duration(relation.to));
+            if excess > 0 {
+                violations.push(Violation::Relation(relation.from, relation.to, relation.kind, excess));
+            }
+        }
+    }
+
+    let mut parents : Vec<u32> = project.activities.iter().map(|x| x.get_parent()).collect();
+    parents.dedup();
+    for parent in parents {
+        let pieces : Vec<&Activity> = project.activities.iter().filter(|x| x.get_parent() == parent).collect();
+        let (min_segment, max_gap) = (pieces[0].min_segment, pieces[0].max_gap);
+        let times : Vec<(i32, u32)> = pieces.iter().filter_map(|x| start_time(x.id).map(|t| (t, x.duration))).collect();
+        if times.len() != pieces.len() {
+            continue;
+        }
+        let segments = project::segments(&times);
+        let total : i32 = segments.iter().map(|(start, finish)| finish - start).sum();
+        for (start, finish) in &segments {
+            if finish - start < total.min(min_segment as i32) {
+                violations.push(Violation::Segment(parent, *start, finish - start, min_segment));
+            }
+        }
+        for pair in segments.windows(2) {
+            if max_gap >= 0 && pair[1].0 - pair[0].1 > max_gap {
+                violations.push(Violation::Gap(parent, pair[0].1, pair[1].0, max_gap));
+            }
+        }
+    }
+
+    if !project.workers.is_empty() {
+        for (id, _) in &starts {
+            let activity = project.activities.iter().find(|x| x.id == *id).unwrap();
+            let assigned : Vec<&Worker> = project.workers.iter().filter(|x| activity.workers.contains(&x.id)).collect();
+            let requirements = project.skill_requirements(activity);
+            let needed : u32 = requirements.iter().map(|x| x.usage).sum();
+            if assigned.len() != activity.workers.len() || needed as usize != assigned.len() || worker::assign(&assigned, &requirements).is_none() {
+                violations.push(Violation::Assignment(*id));
+            }
+        }
+        for w in &project.workers {
+            let jobs : Vec<(u32, i32, i32)> = starts.iter()
+                                                    .map(|(id, t)| (project.activities.iter().find(|x| x.id == *id).unwrap(), *t))
+                                                    .filter(|(a, _)| a.duration > 0 && a.workers.contains(&w.id))
+                                                    .map(|(a, t)| (a.id, t, t + a.duration as i32))
+                                                    .collect();
+            for (i, (id, start, finish)) in jobs.iter().enumerate() {
+                for (other, other_start, other_finish) in &jobs[i + 1..] {
+                    if start < other_finish && other_start < finish {
+                        violations.push(Violation::Worker(w.id, *id, *other));
+                    }
+                }
+            }
+        }
+    }
+
+    for (resource, consumption) in project.nr_consumption() {
+        if consumption > resource.capacity {
+            violations.push(Violation::NonRenewable(resource.id, consumption, resource.capacity));
+        }
+    }
+    violations
+}
+
+#[cfg(test)]
+ mod tests {
+     use crate::prcpsp::validator as validator;
+     use crate::prcpsp::validator::Violation as Violation;
+     use crate::prcpsp::sa_state::SaState as SaState;
+     use crate::prcpsp::random_rcpsp as random_rcpsp;
+     use crate::prcpsp::psplib as psplib;
+     use crate::prcpsp::nr_resource::NrResource as NrResource;
+     use crate::prcpsp::nr_resource_usage::NrResourceUsage as NrResourceUsage;
+
+     #[test]
+     fn test_validate() {
+         let project = psplib::read_sm("examples/psplib-9.sm").unwrap();
+         let planning = vec![1,2,3,4,5,6,8,7,9];
+         let times = vec![0,0,0,0,2,4,5,5,10];
+         assert!(validator::validate(&project, &planning, &times).is_empty());
+
+         let times = vec![0,0,0,0,2,4,5,4,10];
+         assert_eq!(validator::validate(&project, &planning, &times), vec![Violation::Precedence(7,4,6,5)]);
+
+         let times = vec![0,0,0,0,0,4,5,5,10];
+         let violations = validator::validate(&project, &planning, &times);
+         assert_eq!(violations, vec![Violation::Capacity(1,0,7,5), Violation::Capacity(1,1,6,5)]);
+
+         let planning = vec![1,2,3,4,5,6,8,9];
+         let times = vec![0,0,0,0,2,4,5,10];
+         let violations = validator::validate(&project, &planning, &times);
+         assert!(violations.contains(&Violation::Missing(7)));
+
+         let planning = vec![1,2,3,4,5,6,8,9,7];
+         let times = vec![0,0,0,0,2,4,5,10,5];
+         let violations = validator::validate(&project, &planning, &times);
+         assert_eq!(violations, vec![Violation::Dummy(9)]);
+
+         let mut project = project;
+         let budget = NrResource::new(1, String::from("presupuesto"), 10);
+         project.activities[3].consumptions = vec![NrResourceUsage::new(budget.clone(), 6)];
+         project.activities[7].consumptions = vec![NrResourceUsage::new(budget.clone(), 5)];
+         project.nr_resources = vec![budget];
+         let planning = vec![1,2,3,4,5,6,8,7,9];
+         let times = vec![0,0,0,0,2,4,5,5,10];
+         assert_eq!(validator::validate(&project, &planning, &times), vec![Violation::NonRenewable(1,11,10)]);
+     }
+
+     #[test]
+     fn test_validate_states() {
+         let mut project = random_rcpsp::get_random_rcpsp(14, 30, 10, 5, 20);
+         let optimum = SaState::from_project_planned(project.clone(), 0);
+         assert!(validator::validate(&optimum.project, &optimum.planning, &optimum.times).is_empty());
+         for a in &mut project.activities {
+             a.start_time = -1;
+         }
+         let state = SaState::new(project.pre_emptive_project(2), 3);
+         assert!(validator::validate(&state.project, &state.planning, &state.times).is_empty());
+     }
+ }
@@ -0,0 +1,117 @@
+use std::fs;
+
+use crate::prcpsp::project::Project as Project;
+
+/*
+* Time windows of activities, with the release time before which an activity
+* can't start and the deadline when it must be finished.
+*
+* File format (lines starting with '#' are comments):
+*
+*   <activity> <release> [<deadline>]
+*
+* where activity is the id or name of an activity and a deadline of -1 or
+* without value means the activity doesn't have one. Milestones, activities
+* of duration 0, can have deadlines as any other activity.
+*/
+
+/**
+* Read a file of time windows and set them to the activities of a project.
+* project: project whose activities are changed.
+* filename: path to the time windows.
+*/
+pub fn read_windows(project: &mut Project, filename: &str) -> Result<(), String> {
+    let contents = fs::read_to_string(filename)
+            .map_err(|e| format!("No se pudo leer el archivo {}: {}", filename, e))?;
+    parse_windows(project, &contents)
+}
+
+/**
+* Set the time windows of a text to the activities of a project.
+* project: project whose activities are changed.
+* contents: text of the time windows.
+*/
+pub fn parse_windows(project: &mut Project, contents: &str) -> Result<(), String> {
+    for line in contents.lines().map(|x| x.trim()).filter(|x| !x.is_empty() && !x.starts_with('#')) {
+        let tokens : Vec<&str> = line.split_whitespace().collect();
+        if tokens.len() != 2 && tokens.len() != 3 {
+            return Err(format!("Ventana de tiempo incompleta: '{}'", line));
+        }
+        let release = tokens[1].parse::<i32>().map_err(|_| format!("Valor invalido '{}' en '{}'", tokens[1], line))?;
+        let deadline = match tokens.get(2) {
+            Some(t) => t.parse::<i32>().map_err(|_| format!("Valor invalido '{}' en '{}'", t, line))?,
+            None => -1,
+        };
+        let activity = project.activities.iter_mut()
+                                         .find(|x| x.id.to_string() == tokens[0] || x.name == tokens[0])
+                                         .ok_or_else(|| format!("Actividad {} no encontrada en '{}'", tokens[0], line))?;
+        if release < 0 || deadline < -1 || (deadline >= 0 && deadline < release + activity.shortest_duration() as i32) {
+            return Err(format!("Ventana de tiempo invalida '{}'", line));
+        }
+        activity.release = release;
+        activity.deadline = deadline;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+ mod tests {
+     use crate::prcpsp::window as window;
+     use crate::prcpsp::psplib as psplib;
+     use crate::prcpsp::mode::Mode as Mode;
+     use crate::prcpsp::csv_project as csv_project;
+     use crate::prcpsp::sa_state::SaState as SaState;
+     use crate::prcpsp::validator as validator;
+     use crate::prcpsp::validator::Violation as Violation;
+     use crate::metaheuristics::state::State as State;
+
+     #[test]
+     fn test_release() {
+         let mut project = psplib::read_sm("examples/psplib-9.sm").unwrap();
+         window::parse_windows(&mut project, "# entrega\n2 6\n").unwrap();
+         let state = SaState::new(project.pre_emptive_project(1), 14);
+         for (id, time) in state.planning.iter().zip(state.times.iter()) {
+             if state.project.activities.iter().any(|x| x.id == *id && x.parent == 2) {
+                 assert!(*time >= 6);
+             }
+         }
+         assert!(validator::validate(&state.project, &state.planning, &state.times).is_empty());
+
+         assert!(window::parse_windows(&mut project, "2\n").is_err());
+         assert!(window::parse_windows(&mut project, "99 1 5\n").is_err());
+         assert!(window::parse_windows(&mut project, "4 5 6\n").is_err());
+         // A shorter mode can still finish by the deadline
+         let shorter = Mode::new(2, 1, project.activities[3].supplies.clone(), vec![]);
+         project.activities[3].modes = vec![Mode::new(1, project.activities[3].duration, project.activities[3].supplies.clone(), vec![]), shorter];
+         assert!(window::parse_windows(&mut project, "4 5 6\n").is_ok());
+     }
+
+     #[test]
+     fn test_deadline() {
+         let resources = "name,capacity\ncrew,2\n";
+         let activities = "id,name,duration,predecessors,crew,deadline\nA,Walls,3,,2,\nB,Roof,2,,2,\nM,Inspection,0,A,0,4\n";
+         let project = csv_project::parse_csv(activities, resources).unwrap();
+         let pre_emptive = project.pre_emptive_project(2);
+         let milestone = pre_emptive.activities.iter().find(|x| x.parent == 4).unwrap();
+         assert_eq!(pre_emptive.activities.iter().filter(|x| x.parent == 4).count(), 1);
+         assert_eq!((milestone.duration, milestone.deadline), (0, 4));
+         let milestone = milestone.id;
+
+         // Roof first delays the inspection after its deadline
+         let mut state = SaState::new(pre_emptive, 14);
+         let planning : Vec<u32> = state.project.activities.iter().map(|x| x.id).collect();
+         let roof : Vec<u32> = state.project.activities.iter().filter(|x| x.parent == 3).map(|x| x.id).collect();
+         let mut late : Vec<u32> = vec![planning[0]];
+         late.extend(roof.iter());
+         late.extend(planning.iter().filter(|x| **x != planning[0] && !roof.contains(x)));
+         state = SaState::from_planning(state.project, late, 14).unwrap();
+         assert_eq!(state.get_makespan(), 5);
+         assert!(state.get_penalty() > 0);
+         let violations = validator::validate(&state.project, &state.planning, &state.times);
+         assert_eq!(violations, vec![Violation::Deadline(milestone, 5, 4)]);
+
+         let state = SaState::from_planning(state.project, planning, 14).unwrap();
+         assert_eq!(state.get_cost(), (0, 5.0));
+         assert!(validator::validate(&state.project, &state.planning, &state.times).is_empty());
+     }
+ }
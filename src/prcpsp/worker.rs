@@ -0,0 +1,296 @@
+use std::fs;
+
+use crate::prcpsp::project::Project as Project;
+use crate::prcpsp::rn_resource::RnResource as RnResource;
+use crate::prcpsp::rn_resource_usage::RnResourceUsage as RnResourceUsage;
+
+/**
+* Worker with a level in each of its skills, where a skill is a renewable
+* resource whose units are the workers that have it.
+*/
+#[derive(Debug,Clone,PartialEq)]
+pub struct Worker {
+    pub id: u32,
+    pub name: String,
+    pub skills: Vec<(u32, u32)>,
+}
+
+impl Worker {
+    pub fn new(id: u32, name: String, skills: Vec<(u32, u32)>) -> Worker {
+        Worker { id, name, skills }
+    }
+
+    /**
+    * Return the level of the worker in a skill, None if the worker doesn't have it.
+    * resource: skill to check.
+    */
+    pub fn level(&self, resource: &RnResource) -> Option<u32> {
+        self.skills.iter().find(|(id, _)| *id == resource.id).map(|(_, level)| *level)
+    }
+
+    /**
+    * Return true if the worker has the skill of a requirement at its level or higher.
+    * requirement: skill and level needed.
+    */
+    pub fn can_do(&self, requirement: &RnResourceUsage) -> bool {
+        self.level(&requirement.resource).is_some_and(|x| x >= requirement.level)
+    }
+}
+
+/**
+* Assign workers to the requirements of an activity, one worker for each unit
+* of usage and each worker to at most one unit, by maximum bipartite matching.
+* Return the ids of the workers in the order of the requirements,
+* None if the workers can't cover every requirement.
+* workers: available workers.
+* requirements: skills, numbers of workers and levels needed.
+*/
+pub fn assign(workers: &[&Worker], requirements: &[RnResourceUsage]) -> Option<Vec<u32>> {
+    let slots : Vec<&RnResourceUsage> = requirements.iter()
+                                                    .flat_map(|x| std::iter::repeat_n(x, x.usage as usize))
+                                                    .collect();
+    if slots.len() > workers.len() {
+        return None;
+    }
+    // Slot covered by each worker
+    let mut matched : Vec<Option<usize>> = vec![None; workers.len()];
+    for slot in 0..slots.len() {
+        let mut seen = vec![false; workers.len()];
+        if !augment(slot, &slots, workers, &mut matched, &mut seen) {
+            return None;
+        }
+    }
+    let mut assigned = vec![0; slots.len()];
+    for (w, slot) in matched.iter().enumerate() {
+        if let Some(slot) = slot {
+            assigned[*slot] = workers[w].id;
+        }
+    }
+    Some(assigned)
+}
+
+/**
+* Find a worker for a slot, moving the workers already matched to other
+* slots if needed. Return true if the slot is covered.
+*/
+fn augment(slot: usize, slots: &[&RnResourceUsage], workers: &[&Worker], matched: &mut [Option<usize>], seen: &mut [bool]) -> bool {
+    for w in 0..workers.len() {
+        if seen[w] || !workers[w].can_do(slots[slot]) {
+            continue;
+        }
+        seen[w] = true;
+        let free = match matched[w] {
+            Some(other) => augment(other, slots, workers, matched, seen),
+            None => true,
+        };
+        if free {
+            matched[w] = Some(slot);
+            return true;
+        }
+    }
+    false
+}
+
+/*
+* Workers and their skills.
+*
+* File format (lines starting with '#' are comments):
+*
+*   <worker> <skill> <level> [<skill> <level> ..]
+*
+* where worker is the name of the worker, each skill is the id or name of a renewable
+* resource and level the level of the worker in it. The capacity of a skill is the
+* number of workers that have it, replacing the capacity of the resource in the instance.
+*/
+
+/**
+* Read a file of workers and add them to a project.
+* project: project whose workers are added.
+* filename: path to the workers.
+*/
+pub fn read_workers(project: &mut Project, filename: &str) -> Result<(), String> {
+    let contents = fs::read_to_string(filename)
+            .map_err(|e| format!("No se pudo leer el archivo {}: {}", filename, e))?;
+    parse_workers(project, &contents)
+}
+
+/**
+* Add the workers of a text to a project, setting the capacity of each skill to its
+* number of workers whatever its capacity in the project.
+* project: project whose workers are added.
+* contents: text of the workers.
+*/
+pub fn parse_workers(project: &mut Project, contents: &str) -> Result<(), String> {
+    for line in contents.lines().map(|x| x.trim()).filter(|x| !x.is_empty() && !x.starts_with('#')) {
+        let tokens : Vec<&str> = line.split_whitespace().collect();
+        if tokens.len() < 3 || tokens.len().is_multiple_of(2) {
+            return Err(format!("Trabajador incompleto: '{}'", line));
+        }
+        if project.workers.iter().any(|x| x.name == tokens[0]) {
+            return Err(format!("El trabajador {} esta repetido", tokens[0]));
+        }
+        let mut skills : Vec<(u32, u32)> = vec![];
+        for pair in tokens[1..].chunks(2) {
+            let resource = project.resources.iter()
+                                            .find(|x| x.name == pair[0] || x.id.to_string() == pair[0])
+                                            .ok_or_else(|| format!("Recurso {} no encontrado", pair[0]))?;
+            let level = pair[1].parse::<u32>().map_err(|_| format!("Valor invalido '{}' en '{}'", pair[1], line))?;
+            skills.push((resource.id, level));
+        }
+        let id = project.workers.len() as u32 + 1;
+        project.workers.push(Worker::new(id, tokens[0].to_string(), skills));
+    }
+    for resource in &mut project.resources {
+        let workers = project.workers.iter().filter(|x| x.level(resource).is_some()).count() as u32;
+        if workers > 0 {
+            resource.capacity = workers;
+        }
+    }
+    check_requirements(project)
+}
+
+/*
+* Skills required by activities.
+*
+* File format (lines starting with '#' are comments):
+*
+*   <activity> <skill> <workers> [<level>]
+*
+* where activity is the id or name of an activity, skill the id or name of a renewable
+* resource, workers the number of workers with the skill that the activity needs
+* in every mode and level their minimum level in the skill, 0 by default.
+*/
+
+/**
+* Read a file of skill requirements and set them to the activities of a project.
+* project: project whose activities are changed.
+* filename: path to the requirements.
+*/
+pub fn read_skills(project: &mut Project, filename: &str) -> Result<(), String> {
+    let contents = fs::read_to_string(filename)
+            .map_err(|e| format!("No se pudo leer el archivo {}: {}", filename, e))?;
+    parse_skills(project, &contents)
+}
+
+/**
+* Set the skill requirements of a text to the activities of a project.
+* project: project whose activities are changed.
+* contents: text of the requirements.
+*/
+pub fn parse_skills(project: &mut Project, contents: &str) -> Result<(), String> {
+    for line in contents.lines().map(|x| x.trim()).filter(|x| !x.is_empty() && !x.starts_with('#')) {
+        let tokens : Vec<&str> = line.split_whitespace().collect();
+        if tokens.len() != 3 && tokens.len() != 4 {
+            return Err(format!("Habilidad incompleta: '{}'", line));
+        }
+        let resource = project.resources.iter()
+                                        .find(|x| x.name == tokens[1] || x.id.to_string() == tokens[1])
+                                        .ok_or_else(|| format!("Recurso {} no encontrado", tokens[1]))?
+                                        .clone();
+        let number = |i: usize| match tokens.get(i) {
+            Some(t) => t.parse::<u32>().map_err(|_| format!("Valor invalido '{}' en '{}'", t, line)),
+            None => Ok(0),
+        };
+        let usage = number(2)?;
+        let level = number(3)?;
+        let activity = project.activities.iter_mut()
+                                         .find(|x| x.id.to_string() == tokens[0] || x.name == tokens[0])
+                                         .ok_or_else(|| format!("Actividad {} no encontrada en '{}'", tokens[0], line))?;
+        for supplies in std::iter::once(&mut activity.supplies).chain(activity.modes.iter_mut().map(|x| &mut x.supplies)) {
+            match supplies.iter_mut().find(|x| x.resource == resource) {
+                Some(supply) => supply.usage = usage,
+                None => supplies.push(RnResourceUsage::new(resource.clone(), usage)),
+            }
+            for supply in supplies.iter_mut().filter(|x| x.resource == resource) {
+                supply.level = level;
+            }
+            supplies.retain(|x| x.usage > 0);
+        }
+    }
+    check_requirements(project)
+}
+
+/**
+* Check that the workers of a project can cover the skills required by each
+* activity in every mode, also when it's resumed after an interruption.
+* project: project to check.
+*/
+fn check_requirements(project: &Project) -> Result<(), String> {
+    if project.workers.is_empty() {
+        return Ok(());
+    }
+    let workers : Vec<&Worker> = project.workers.iter().collect();
+    for activity in &project.activities {
+        for mode in 0..activity.modes.len().max(1) {
+            let mut current = activity.clone();
+            current.set_mode(mode);
+            for resumed in &[false, true] {
+                current.set_resumed(*resumed);
+                if assign(&workers, &project.skill_requirements(&current)).is_none() {
+                    return Err(format!("Los trabajadores no cubren las habilidades de la actividad {}", activity.name));
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+ mod tests {
+     use crate::prcpsp::worker as worker;
+     use crate::prcpsp::csv_project as csv_project;
+     use crate::prcpsp::instance as instance;
+     use crate::prcpsp::solution::Solution as Solution;
+     use crate::prcpsp::sa_state::SaState as SaState;
+     use crate::prcpsp::validator as validator;
+     use crate::prcpsp::validator::Violation as Violation;
+
+     const WORKERS : &str = "# Ana es la unica electricista con nivel 2
+Ana electric 2 plumbing 1
+Luis electric 1
+Eva plumbing 2
+";
+
+     #[test]
+     fn test_workers() {
+         let resources = "name,capacity\nelectric,2\nplumbing,2\n";
+         let activities = "id,name,duration,predecessors,electric,plumbing\nA,Wiring,2,,1,\nB,Pipes,2,,,1\nC,Boiler,1,,1,1\n";
+         let mut project = csv_project::parse_csv(activities, resources).unwrap();
+         worker::parse_workers(&mut project, WORKERS).unwrap();
+         worker::parse_skills(&mut project, "Wiring electric 1 2\nBoiler plumbing 1 2\n").unwrap();
+         assert_eq!(project.workers.len(), 3);
+         assert_eq!(project.resources.iter().map(|x| x.capacity).collect::<Vec<u32>>(), vec![2, 2]);
+
+         // The number of workers replaces a different capacity given in the instance
+         let mut other = csv_project::parse_csv(activities, "name,capacity\nelectric,5\nplumbing,1\n").unwrap();
+         worker::parse_workers(&mut other, WORKERS).unwrap();
+         assert_eq!(other.resources.iter().map(|x| x.capacity).collect::<Vec<u32>>(), vec![2, 2]);
+
+         // Wiring needs Ana, so Pipes takes Eva and Boiler waits for Ana or Luis and Eva
+         let state = SaState::from_planning(project.pre_emptive_project(0), vec![1,2,3,4,5], 14).unwrap();
+         let workers : Vec<Vec<u32>> = state.project.activities.iter().map(|x| x.workers.clone()).collect();
+         assert_eq!(workers, vec![vec![], vec![1], vec![3], vec![1,3], vec![]]);
+         assert_eq!(state.times, vec![0,0,0,2,3]);
+         assert!(validator::validate(&state.project, &state.planning, &state.times).is_empty());
+
+         let mut wrong = state.project.clone();
+         wrong.activities[2].workers = vec![1];
+         wrong.activities[3].workers = vec![2,1];
+         let violations = validator::validate(&wrong, &state.planning, &state.times);
+         assert_eq!(violations, vec![Violation::Assignment(4), Violation::Worker(1, 2, 3)]);
+
+         let solution = Solution::parse(&Solution::from_state(&state, "workers", 0).get_string()).unwrap();
+         let mut loaded = project.pre_emptive_project(0);
+         solution.set_workers(&mut loaded).unwrap();
+         assert!(validator::validate(&loaded, &solution.planning, &solution.times).is_empty());
+
+         let other = instance::parse_instance(&instance::to_string(&project)).unwrap();
+         assert_eq!(other.workers, project.workers);
+         assert_eq!(other.activities[1].supplies[0].level, 2);
+
+         assert!(worker::parse_skills(&mut project.clone(), "Wiring electric 1 3\n").is_err());
+         assert!(worker::parse_skills(&mut project.clone(), "Boiler electric 2 2\n").is_err());
+         assert!(worker::parse_workers(&mut project.clone(), "Ana electric 1\n").is_err());
+         assert!(worker::parse_workers(&mut project.clone(), "Raul carpentry 1\n").is_err());
+     }
+ }